@@ -0,0 +1,143 @@
+//! Setup checks for the addresses of the devices in a rack.
+
+use core::fmt;
+
+use crate::DeviceDescriptor;
+use crate::devices::*;
+
+/// Identifies a supported device, e.g. in a host app's list of configured modules.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DeviceKind {
+    /// Monome Ansible.
+    Ansible,
+    /// Monome Crow.
+    Crow,
+    /// Expert Sleepers Disting EX.
+    DistingEx,
+    /// Orthogonal Devices ER-301.
+    Er301,
+    /// 16n Faderbank.
+    Faderbank,
+    /// Expert Sleepers FH-2.
+    Fh2,
+    /// Mannequins Just Friends.
+    JustFriends,
+    /// A Monome Teletype in follower mode.
+    Teletype,
+    /// BPC TELEXi (TXi).
+    Telexi,
+    /// BPC TELEXo (TXo).
+    Telexo,
+    /// Mannequins W/.
+    W,
+}
+
+impl DeviceKind {
+    /// Returns the metadata of the device.
+    pub const fn descriptor(self) -> &'static DeviceDescriptor {
+        match self {
+            Self::Ansible => &ansible::DESCRIPTOR,
+            Self::Crow => &crow::DESCRIPTOR,
+            Self::DistingEx => &disting_ex::DESCRIPTOR,
+            Self::Er301 => &er301::DESCRIPTOR,
+            Self::Faderbank => &faderbank::DESCRIPTOR,
+            Self::Fh2 => &fh2::DESCRIPTOR,
+            Self::JustFriends => &just_friends::DESCRIPTOR,
+            Self::Teletype => &teletype::DESCRIPTOR,
+            Self::Telexi => &telexi::DESCRIPTOR,
+            Self::Telexo => &telexo::DESCRIPTOR,
+            Self::W => &w::DESCRIPTOR,
+        }
+    }
+}
+
+/// The first problem found by [`check_addresses`]. Entries are named by their position
+/// in the checked slice.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum AddressConflict {
+    /// Two entries use the same address, so both devices would answer the same messages.
+    Duplicate {
+        /// The position of the earlier entry.
+        first: usize,
+        /// The position of the later entry.
+        second: usize,
+        /// The shared address.
+        address: u8,
+    },
+    /// An entry uses an address the device cannot be configured to answer on.
+    OutOfRange {
+        /// The position of the entry.
+        entry: usize,
+        /// The device of the entry.
+        kind: DeviceKind,
+        /// The configured address.
+        address: u8,
+    },
+}
+
+impl fmt::Display for AddressConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::Duplicate {
+                first,
+                second,
+                address,
+            } => write!(
+                f,
+                "entries {first} and {second} both use address 0x{address:02X}"
+            ),
+            Self::OutOfRange {
+                entry,
+                kind,
+                address,
+            } => write!(
+                f,
+                "entry {entry} uses address 0x{address:02X}, which the {} cannot answer on",
+                kind.descriptor().name
+            ),
+        }
+    }
+}
+
+impl core::error::Error for AddressConflict {}
+
+/// Checks the addresses of the configured devices, e.g. before a host app starts sending.
+///
+/// Each entry pairs a device with the address it is set to. Every address must lie in
+/// the device's [`DeviceDescriptor::address_range`] and no two entries may share one.
+/// Entries are checked in order, and the first conflict is returned.
+///
+/// ```
+/// use mii::{AddressConflict, DeviceKind, check_addresses};
+///
+/// let rack = [(DeviceKind::Telexo, 0x60), (DeviceKind::Telexo, 0x61)];
+/// assert_eq!(check_addresses(&rack), Ok(()));
+///
+/// let rack = [(DeviceKind::Telexo, 0x60), (DeviceKind::Telexo, 0x60)];
+/// assert_eq!(
+///     check_addresses(&rack),
+///     Err(AddressConflict::Duplicate { first: 0, second: 1, address: 0x60 })
+/// );
+/// ```
+pub fn check_addresses(devices: &[(DeviceKind, u8)]) -> Result<(), AddressConflict> {
+    for (entry, &(kind, address)) in devices.iter().enumerate() {
+        if !kind.descriptor().address_range.contains(&address) {
+            return Err(AddressConflict::OutOfRange {
+                entry,
+                kind,
+                address,
+            });
+        }
+        if let Some(first) = devices[..entry].iter().position(|&(_, a)| a == address) {
+            return Err(AddressConflict::Duplicate {
+                first,
+                second: entry,
+                address,
+            });
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,231 @@
+//! A single command type covering every supported device.
+//!
+//! [`AnyCommand`] carries a device's command together with everything needed to resolve
+//! its I2C address, so one transmit queue can hold commands for several modules.
+
+use core::fmt;
+
+use crate::devices::*;
+use crate::{
+    Command, CommandCategory, MAX_MESSAGE_LENGTH, SerializationError, TargetKey, TeletypeOp,
+};
+
+/// A command for any supported device, with its target address attached.
+///
+/// Devices whose address depends on a jumper or menu setting carry the device `index`
+/// that their module's `address` function expects. The 16n Faderbank is not included,
+/// since its messages are sent to the follower's address rather than its own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AnyCommand {
+    /// A command for Ansible.
+    Ansible(ansible::Commands),
+    /// A command for Crow.
+    Crow(crow::Commands),
+    /// A command for the Disting EX with the given index (0-3).
+    DistingEx {
+        index: u8,
+        cmd: disting_ex::Commands,
+    },
+    /// A command for the ER-301 with the given index (0-2).
+    Er301 { index: u8, cmd: er301::Commands },
+    /// A command for the FH-2.
+    Fh2(fh2::Commands),
+    /// A command for Just Friends.
+    JustFriends(just_friends::Commands),
+    /// A command for the Teletype follower with the given index (0-3).
+    Teletype { index: u8, cmd: teletype::Commands },
+    /// A command for the TXi with the given index (0-7).
+    Telexi { index: u8, cmd: telexi::Commands },
+    /// A command for the TXo with the given index (0-7).
+    Telexo { index: u8, cmd: telexo::Commands },
+    /// A command for W/.
+    W(w::Commands),
+}
+
+impl AnyCommand {
+    /// Resolves the I2C address the command is sent to.
+    ///
+    /// Returns `None` if the device index is out of range.
+    pub const fn address(&self) -> Option<u8> {
+        match *self {
+            Self::Ansible(_) => Some(ansible::ADDRESS),
+            Self::Crow(_) => Some(crow::ADDRESS),
+            Self::DistingEx { index, .. } => disting_ex::address(index),
+            Self::Er301 { index, .. } => er301::address(index),
+            Self::Fh2(_) => Some(fh2::ADDRESS),
+            Self::JustFriends(_) => Some(just_friends::ADDRESS),
+            Self::Teletype { index, .. } => teletype::address(index),
+            Self::Telexi { index, .. } => telexi::address(index),
+            Self::Telexo { index, .. } => telexo::address(index),
+            Self::W(_) => Some(w::ADDRESS),
+        }
+    }
+
+    /// Returns the category of the wrapped command.
+    pub const fn category(&self) -> CommandCategory {
+        match self {
+            Self::Ansible(cmd) => cmd.category(),
+            Self::Crow(cmd) => cmd.category(),
+            Self::DistingEx { cmd, .. } => cmd.category(),
+            Self::Er301 { cmd, .. } => cmd.category(),
+            Self::Fh2(cmd) => cmd.category(),
+            Self::JustFriends(cmd) => cmd.category(),
+            Self::Teletype { cmd, .. } => cmd.category(),
+            Self::Telexi { cmd, .. } => cmd.category(),
+            Self::Telexo { cmd, .. } => cmd.category(),
+            Self::W(cmd) => cmd.category(),
+        }
+    }
+
+    /// Returns the equivalent Teletype op of the wrapped command, see e.g.
+    /// [`er301::Commands::teletype_op`].
+    pub const fn teletype_op(&self) -> Option<TeletypeOp<Self>> {
+        let has_op = match self {
+            Self::Ansible(cmd) => cmd.teletype_op().is_some(),
+            Self::Crow(cmd) => cmd.teletype_op().is_some(),
+            Self::DistingEx { cmd, .. } => cmd.teletype_op().is_some(),
+            Self::Er301 { cmd, .. } => cmd.teletype_op().is_some(),
+            Self::Fh2(cmd) => cmd.teletype_op().is_some(),
+            Self::JustFriends(cmd) => cmd.teletype_op().is_some(),
+            Self::Teletype { cmd, .. } => cmd.teletype_op().is_some(),
+            Self::Telexi { cmd, .. } => cmd.teletype_op().is_some(),
+            Self::Telexo { cmd, .. } => cmd.teletype_op().is_some(),
+            Self::W(cmd) => cmd.teletype_op().is_some(),
+        };
+        if has_op {
+            Some(TeletypeOp(*self))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the target address paired with the command's [`TargetKey`], which
+    /// identifies its destination across all devices.
+    ///
+    /// Returns `None` if the command has no key or the device index is out of range.
+    pub const fn target_key(&self) -> Option<(u8, TargetKey)> {
+        let key = match self {
+            Self::Ansible(cmd) => cmd.target_key(),
+            Self::Crow(cmd) => cmd.target_key(),
+            Self::DistingEx { cmd, .. } => cmd.target_key(),
+            Self::Er301 { cmd, .. } => cmd.target_key(),
+            Self::Fh2(cmd) => cmd.target_key(),
+            Self::JustFriends(cmd) => cmd.target_key(),
+            Self::Teletype { cmd, .. } => cmd.target_key(),
+            Self::Telexi { cmd, .. } => cmd.target_key(),
+            Self::Telexo { cmd, .. } => cmd.target_key(),
+            Self::W(cmd) => cmd.target_key(),
+        };
+        match (self.address(), key) {
+            (Some(address), Some(key)) => Some((address, key)),
+            _ => None,
+        }
+    }
+}
+
+impl Command for AnyCommand {
+    const MAX_LENGTH: usize = MAX_MESSAGE_LENGTH;
+
+    fn to_bytes<'a>(&self, buffer: &'a mut [u8]) -> Result<&'a [u8], SerializationError> {
+        match self {
+            Self::Ansible(cmd) => cmd.to_bytes(buffer),
+            Self::Crow(cmd) => cmd.to_bytes(buffer),
+            Self::DistingEx { cmd, .. } => cmd.to_bytes(buffer),
+            Self::Er301 { cmd, .. } => cmd.to_bytes(buffer),
+            Self::Fh2(cmd) => cmd.to_bytes(buffer),
+            Self::JustFriends(cmd) => cmd.to_bytes(buffer),
+            Self::Teletype { cmd, .. } => cmd.to_bytes(buffer),
+            Self::Telexi { cmd, .. } => cmd.to_bytes(buffer),
+            Self::Telexo { cmd, .. } => cmd.to_bytes(buffer),
+            Self::W(cmd) => cmd.to_bytes(buffer),
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            Self::Ansible(cmd) => cmd.len(),
+            Self::Crow(cmd) => cmd.len(),
+            Self::DistingEx { cmd, .. } => cmd.len(),
+            Self::Er301 { cmd, .. } => cmd.len(),
+            Self::Fh2(cmd) => cmd.len(),
+            Self::JustFriends(cmd) => cmd.len(),
+            Self::Teletype { cmd, .. } => cmd.len(),
+            Self::Telexi { cmd, .. } => cmd.len(),
+            Self::Telexo { cmd, .. } => cmd.len(),
+            Self::W(cmd) => cmd.len(),
+        }
+    }
+
+    fn address(&self, _index: u8) -> Option<u8> {
+        Self::address(self)
+    }
+}
+
+/// Formats the wrapped command with its device's mnemonic, see e.g. [`er301::Commands`].
+impl fmt::Display for AnyCommand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Ansible(cmd) => cmd.fmt(f),
+            Self::Crow(cmd) => cmd.fmt(f),
+            Self::DistingEx { cmd, .. } => cmd.fmt(f),
+            Self::Er301 { cmd, .. } => cmd.fmt(f),
+            Self::Fh2(cmd) => cmd.fmt(f),
+            Self::JustFriends(cmd) => cmd.fmt(f),
+            Self::Teletype { cmd, .. } => cmd.fmt(f),
+            Self::Telexi { cmd, .. } => cmd.fmt(f),
+            Self::Telexo { cmd, .. } => cmd.fmt(f),
+            Self::W(cmd) => cmd.fmt(f),
+        }
+    }
+}
+
+/// Formats the wrapped command as its device's Teletype op.
+impl fmt::Display for TeletypeOp<AnyCommand> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            AnyCommand::Ansible(cmd) => TeletypeOp(cmd).fmt(f),
+            AnyCommand::Crow(cmd) => TeletypeOp(cmd).fmt(f),
+            AnyCommand::DistingEx { cmd, .. } => TeletypeOp(cmd).fmt(f),
+            AnyCommand::Er301 { cmd, .. } => TeletypeOp(cmd).fmt(f),
+            // FH-2 commands have no op, so they are never wrapped.
+            AnyCommand::Fh2(cmd) => cmd.fmt(f),
+            AnyCommand::JustFriends(cmd) => TeletypeOp(cmd).fmt(f),
+            AnyCommand::Teletype { cmd, .. } => TeletypeOp(cmd).fmt(f),
+            AnyCommand::Telexi { cmd, .. } => TeletypeOp(cmd).fmt(f),
+            AnyCommand::Telexo { cmd, .. } => TeletypeOp(cmd).fmt(f),
+            AnyCommand::W(cmd) => TeletypeOp(cmd).fmt(f),
+        }
+    }
+}
+
+impl From<ansible::Commands> for AnyCommand {
+    fn from(cmd: ansible::Commands) -> Self {
+        Self::Ansible(cmd)
+    }
+}
+
+impl From<crow::Commands> for AnyCommand {
+    fn from(cmd: crow::Commands) -> Self {
+        Self::Crow(cmd)
+    }
+}
+
+impl From<fh2::Commands> for AnyCommand {
+    fn from(cmd: fh2::Commands) -> Self {
+        Self::Fh2(cmd)
+    }
+}
+
+impl From<just_friends::Commands> for AnyCommand {
+    fn from(cmd: just_friends::Commands) -> Self {
+        Self::JustFriends(cmd)
+    }
+}
+
+impl From<w::Commands> for AnyCommand {
+    fn from(cmd: w::Commands) -> Self {
+        Self::W(cmd)
+    }
+}
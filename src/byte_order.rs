@@ -0,0 +1,431 @@
+//! Byte order checks for every 16-bit field of every command.
+//!
+//! II sends multi-byte fields big-endian. Each field below is set to a value whose bytes
+//! differ, e.g. `0x0102`, so a little-endian encoding cannot pass by accident.
+
+use core::fmt::Debug;
+
+use crate::devices::*;
+use crate::{Command, put_i16_be, put_u16_be};
+
+fn check_big_endian<C: Command + Debug>(vectors: &[(C, &[u8])]) {
+    let mut buffer = [0u8; 16];
+    for (command, expected) in vectors {
+        assert_eq!(command.to_bytes(&mut buffer), Ok(*expected), "{command:?}");
+    }
+}
+
+#[test]
+fn helpers_write_most_significant_byte_first() {
+    let mut buffer = [0u8; 5];
+    put_u16_be(&mut buffer, 1, 0x0102);
+    put_i16_be(&mut buffer, 3, -2);
+    assert_eq!(buffer, [0x00, 0x01, 0x02, 0xFF, 0xFE]);
+}
+
+// 16-bit fields are sent MSB first, per Ansible's II reference (`ANS.*`, `KR.*`, `MP.*` ops).
+#[test]
+fn ansible() {
+    use ansible::Commands::*;
+    check_big_endian(&[
+        (
+            SetCv {
+                port: 0,
+                value: 0x0102,
+            },
+            &[0x01, 0x00, 0x01, 0x02],
+        ),
+        (
+            SetCvSlew {
+                port: 0,
+                ms: 0x0102,
+            },
+            &[0x02, 0x00, 0x01, 0x02],
+        ),
+        (
+            SetCvFromFader {
+                device_port: 0,
+                value: 0x0102,
+            },
+            &[0x06, 0x00, 0x01, 0x02],
+        ),
+        (
+            SetTrPulseDuration {
+                port: 0,
+                ms: 0x0102,
+            },
+            &[0x13, 0x00, 0x01, 0x02],
+        ),
+        (KriaSetTempo { bpm: 0x0102 }, &[0x31, 0x01, 0x02]),
+        (
+            MeadowphysicsSetSpeed {
+                row: 0,
+                speed: 0x0102,
+            },
+            &[0x42, 0x00, 0x01, 0x02],
+        ),
+        (
+            LevelsSetValue {
+                track: 1,
+                value: 0x0102,
+            },
+            &[0x50, 0x01, 0x01, 0x02],
+        ),
+        (
+            CyclesSetFreq {
+                channel: 0,
+                freq: 0x0102,
+            },
+            &[0x60, 0x00, 0x01, 0x02],
+        ),
+    ]);
+}
+
+// 16-bit fields are sent MSB first, per Crow's II reference (`CROW.*` ops).
+#[test]
+fn crow() {
+    use crow::Commands::*;
+    check_big_endian(&[
+        (
+            SetOutput {
+                channel: 1,
+                value: 0x0102,
+            },
+            &[0x01, 0x01, 0x01, 0x02],
+        ),
+        (
+            CallFunction {
+                index: 1,
+                arg: 0x0102,
+            },
+            &[0x02, 0x01, 0x01, 0x02],
+        ),
+    ]);
+}
+
+// 16-bit fields are sent MSB first, per the Disting EX manual, I2C section.
+#[test]
+fn disting_ex() {
+    use disting_ex::Commands::*;
+    check_big_endian(&[
+        (
+            SetParameter {
+                param: 0,
+                value: 0x0102,
+            },
+            &[0x46, 0x00, 0x01, 0x02],
+        ),
+        (
+            SetZModeParameter {
+                param: 0,
+                value: 0x0102,
+            },
+            &[0x4A, 0x00, 0x01, 0x02],
+        ),
+    ]);
+}
+
+// 16-bit fields are sent MSB first, per the ER-301 Teletype ops (`SC.*`).
+#[test]
+fn er301() {
+    use er301::Commands::*;
+    check_big_endian(&[
+        (
+            SetTrTime {
+                port: 0,
+                ms: 0x0102,
+            },
+            &[0x15, 0x00, 0x01, 0x02],
+        ),
+        (
+            SetCv {
+                port: 0,
+                value: 0x0102,
+            },
+            &[0x11, 0x00, 0x01, 0x02],
+        ),
+        (
+            SetCvImmediate {
+                port: 0,
+                value: 0x0102,
+            },
+            &[0x10, 0x00, 0x01, 0x02],
+        ),
+        (
+            SetCvSlew {
+                port: 0,
+                ms: 0x0102,
+            },
+            &[0x12, 0x00, 0x01, 0x02],
+        ),
+    ]);
+}
+
+// 16-bit fields are sent MSB first, per the 16n firmware's II leader mode.
+#[test]
+fn faderbank() {
+    use faderbank::Commands::*;
+    check_big_endian(&[(
+        SetCvFromFader {
+            device_port: 0,
+            value: 0x0102,
+        },
+        &[0x06, 0x00, 0x01, 0x02],
+    )]);
+}
+
+// 16-bit fields are sent MSB first, per the FH-2 manual, I2C section.
+#[test]
+fn fh2() {
+    use fh2::Commands::*;
+    check_big_endian(&[
+        (
+            SetCv {
+                channel: 0,
+                value: 0x0102,
+            },
+            &[0x11, 0x00, 0x01, 0x02],
+        ),
+        (SetClockBpm { bpm: 0x0102 }, &[0x21, 0x01, 0x02]),
+    ]);
+}
+
+// 16-bit fields are sent MSB first, per the Just Friends II reference (`JF.*` ops).
+#[test]
+fn just_friends() {
+    use just_friends::Commands::*;
+    check_big_endian(&[
+        (
+            PlayNote {
+                output: 0,
+                pitch: 0x0102,
+                volume: 0x0304,
+            },
+            &[0x08, 0x00, 0x01, 0x02, 0x03, 0x04],
+        ),
+        (
+            SetVoice {
+                channel: 0,
+                pitch: 0x0102,
+                level: 0x0304,
+            },
+            &[0x09, 0x00, 0x01, 0x02, 0x03, 0x04],
+        ),
+        (
+            SetIntone {
+                channel: 0,
+                ratio: 0x0102,
+            },
+            &[0x15, 0x00, 0x01, 0x02],
+        ),
+        (SetRun { value: 0x0102 }, &[0x03, 0x01, 0x02]),
+        (SetTranspose { value: 0x0102 }, &[0x04, 0x01, 0x02]),
+        (SetRamp { value: 0x0102 }, &[0x11, 0x01, 0x02]),
+        (SetCurve { value: 0x0102 }, &[0x12, 0x01, 0x02]),
+        (SetFm { value: 0x0102 }, &[0x13, 0x01, 0x02]),
+        (
+            Tune {
+                channel: 0,
+                value: 0x0102,
+            },
+            &[0x0B, 0x00, 0x01, 0x02],
+        ),
+        (
+            SetTime {
+                channel: 0,
+                value: 0x0102,
+            },
+            &[0x14, 0x00, 0x01, 0x02],
+        ),
+    ]);
+}
+
+// 16-bit fields are sent MSB first, per Teletype's II follower ops (`TT.*`).
+#[test]
+fn teletype() {
+    use teletype::Commands::*;
+    check_big_endian(&[(
+        SetPattern {
+            index: 0,
+            value: 0x0102,
+        },
+        &[0x02, 0x00, 0x01, 0x02],
+    )]);
+}
+
+// 16-bit fields are sent MSB first, per the TELEXi Teletype reference (`TI.*` ops).
+#[test]
+fn telexi() {
+    use telexi::Commands::*;
+    check_big_endian(&[
+        (
+            SetInputScale {
+                port: 0,
+                min: 0x0102,
+                max: 0x0304,
+            },
+            &[0x03, 0x00, 0x01, 0x02, 0x03, 0x04],
+        ),
+        (
+            SetInputMap {
+                port: 0,
+                top: 0x0102,
+                bottom: 0x0304,
+            },
+            &[0x04, 0x00, 0x01, 0x02, 0x03, 0x04],
+        ),
+        (
+            SetParamScale {
+                port: 0,
+                min: 0x0102,
+                max: 0x0304,
+            },
+            &[0x13, 0x00, 0x01, 0x02, 0x03, 0x04],
+        ),
+        (
+            SetParamMap {
+                port: 0,
+                top: 0x0102,
+                bottom: 0x0304,
+            },
+            &[0x14, 0x00, 0x01, 0x02, 0x03, 0x04],
+        ),
+    ]);
+}
+
+// 16-bit fields are sent MSB first, per the TELEXo Teletype reference (`TO.*` ops).
+#[test]
+fn telexo() {
+    use telexo::Commands::*;
+    check_big_endian(&[
+        (
+            SetTrTime {
+                port: 0,
+                ms: 0x0102,
+            },
+            &[0x15, 0x00, 0x01, 0x02],
+        ),
+        (
+            SetCv {
+                port: 0,
+                value: 0x0102,
+            },
+            &[0x11, 0x00, 0x01, 0x02],
+        ),
+        (
+            SetCvImmediate {
+                port: 0,
+                value: 0x0102,
+            },
+            &[0x10, 0x00, 0x01, 0x02],
+        ),
+        (
+            SetCvSlew {
+                port: 0,
+                ms: 0x0102,
+            },
+            &[0x12, 0x00, 0x01, 0x02],
+        ),
+        (
+            SetCvOffset {
+                port: 0,
+                value: 0x0102,
+            },
+            &[0x17, 0x00, 0x01, 0x02],
+        ),
+        (
+            SetOscPitch {
+                port: 0,
+                pitch: 0x0102,
+            },
+            &[0x41, 0x00, 0x01, 0x02],
+        ),
+        (
+            SetOscPhase {
+                port: 0,
+                phase: 0x0102,
+            },
+            &[0x44, 0x00, 0x01, 0x02],
+        ),
+        (
+            SetOscLfoRate {
+                port: 0,
+                rate: 0x0102,
+            },
+            &[0x46, 0x00, 0x01, 0x02],
+        ),
+        (
+            SetOscFreq {
+                port: 0,
+                freq: 0x0102,
+            },
+            &[0x48, 0x00, 0x01, 0x02],
+        ),
+        (
+            SetOscSlew {
+                port: 0,
+                ms: 0x0102,
+            },
+            &[0x49, 0x00, 0x01, 0x02],
+        ),
+        (
+            SetOscWaveform {
+                port: 0,
+                waveform: 0x0102,
+            },
+            &[0x4A, 0x00, 0x01, 0x02],
+        ),
+        // The width is at most 100, so its high byte is always zero.
+        (
+            SetOscWidth {
+                port: 0,
+                width: 100,
+            },
+            &[0x4B, 0x00, 0x00, 0x64],
+        ),
+        (
+            SetEnvelopeAttack {
+                port: 0,
+                ms: 0x0102,
+            },
+            &[0x61, 0x00, 0x01, 0x02],
+        ),
+        (
+            SetEnvelopeDecay {
+                port: 0,
+                ms: 0x0102,
+            },
+            &[0x62, 0x00, 0x01, 0x02],
+        ),
+        (SetMetroTime { ms: 0x0102 }, &[0x30, 0x01, 0x02]),
+        (SetMetroBpm { bpm: 0x0102 }, &[0x32, 0x01, 0x02]),
+        (
+            SetOscCenter {
+                port: 0,
+                value: 0x0102,
+            },
+            &[0x4D, 0x00, 0x01, 0x02],
+        ),
+    ]);
+}
+
+// 16-bit fields are sent MSB first, per the W/ II reference (`W/.*` ops).
+#[test]
+fn w() {
+    use w::Commands::*;
+    check_big_endian(&[
+        (Play { speed: 0x0102 }, &[0x01, 0x01, 0x02]),
+        (
+            Loop {
+                start: 0x0102,
+                end: 0x0304,
+            },
+            &[0x03, 0x01, 0x02, 0x03, 0x04],
+        ),
+        (SetSpeed { value: 0x0102 }, &[0x04, 0x01, 0x02]),
+        (SetLoopStart { position: 0x0102 }, &[0x05, 0x01, 0x02]),
+        (SetLoopEnd { position: 0x0102 }, &[0x06, 0x01, 0x02]),
+        (CueTo { position: 0x0102 }, &[0x07, 0x01, 0x02]),
+        (SetRecordThreshold { value: 0x0102 }, &[0x09, 0x01, 0x02]),
+    ]);
+}
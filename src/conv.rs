@@ -0,0 +1,176 @@
+//! Conversions between musical units and the raw values carried by CV fields.
+//!
+//! Devices such as the TXo and the ER-301 use the Teletype scaling, where a value of
+//! 16384 corresponds to 10V, i.e. 1638.4 per volt and 136.53 per semitone at 1V/oct.
+//! The `f32` helpers work on any target, but are slow without an FPU; the
+//! millivolt helpers only use integer arithmetic. All conversions round to the nearest
+//! value and saturate at the limits of `i16`; [`Pitch`] rounds ties to even, the other
+//! helpers away from zero.
+
+/// The raw CV value corresponding to one volt.
+pub const CV_PER_VOLT: f32 = 1638.4;
+
+/// The raw CV value corresponding to 10V.
+const CV_PER_10_VOLTS: i64 = 16384;
+
+/// Rounds half away from zero and saturates to the range of `i16`.
+fn round_to_i16(value: f32) -> i16 {
+    if value < 0.0 {
+        (value - 0.5) as i16
+    } else {
+        (value + 0.5) as i16
+    }
+}
+
+/// Converts a voltage to a raw CV value.
+pub fn volts_to_cv(volts: f32) -> i16 {
+    round_to_i16(volts * CV_PER_VOLT)
+}
+
+/// Converts a pitch in semitones above 0V to a raw CV value at 1V/oct.
+pub fn semitones_to_cv(semitones: f32) -> i16 {
+    round_to_i16(semitones * CV_PER_VOLT / 12.0)
+}
+
+/// Converts a raw CV value to a voltage.
+pub fn cv_to_volts(cv: i16) -> f32 {
+    f32::from(cv) / CV_PER_VOLT
+}
+
+/// Converts a voltage in millivolts to a raw CV value, using integer arithmetic only.
+pub fn millivolts_to_cv(millivolts: i32) -> i16 {
+    let scaled = i64::from(millivolts) * CV_PER_10_VOLTS;
+    let rounded = if scaled < 0 {
+        (scaled - 5000) / 10000
+    } else {
+        (scaled + 5000) / 10000
+    };
+    rounded.clamp(i64::from(i16::MIN), i64::from(i16::MAX)) as i16
+}
+
+/// Converts a raw CV value to a voltage in millivolts, using integer arithmetic only.
+pub fn cv_to_millivolts(cv: i16) -> i32 {
+    let scaled = i64::from(cv) * 10000;
+    let half = CV_PER_10_VOLTS / 2;
+    let rounded = if scaled < 0 {
+        (scaled - half) / CV_PER_10_VOLTS
+    } else {
+        (scaled + half) / CV_PER_10_VOLTS
+    };
+    rounded as i32
+}
+
+/// Converts a signed number of semitones to a raw CV value at 1V/oct, using integer
+/// arithmetic only.
+const fn semitone_offset_to_cv(semitones: i32) -> i16 {
+    let scaled = semitones as i64 * CV_PER_10_VOLTS;
+    let rounded = if scaled < 0 {
+        (scaled - 60) / 120
+    } else {
+        (scaled + 60) / 120
+    };
+    rounded as i16
+}
+
+/// The MIDI note Just Friends plays when it receives a pitch of 0 (C4).
+pub const JF_REFERENCE_NOTE: u8 = 60;
+
+/// The MIDI note the TXo plays when it receives a pitch of 0 (C-1).
+pub const TXO_REFERENCE_NOTE: u8 = 0;
+
+/// Converts a MIDI note number to a pitch for Just Friends commands such as `PlayNote`.
+///
+/// Just Friends pitches are relative to its tuned base note, which is taken to be
+/// middle C (MIDI note 60). Lower notes produce negative pitches.
+pub const fn midi_note_to_jf_pitch(note: u8) -> i16 {
+    semitone_offset_to_cv(note as i32 - JF_REFERENCE_NOTE as i32)
+}
+
+/// Converts a MIDI note number to a pitch for TXo oscillator commands such as
+/// `SetOscPitch`.
+///
+/// The TXo maps a pitch of 0 to MIDI note 0, so every MIDI note produces a
+/// non-negative pitch.
+pub const fn midi_note_to_txo_pitch(note: u8) -> i16 {
+    semitone_offset_to_cv(note as i32 - TXO_REFERENCE_NOTE as i32)
+}
+
+/// Divides and rounds half to even, the rounding used by [`Pitch`].
+pub(crate) const fn div_round_half_even(numerator: i64, denominator: i64) -> i64 {
+    let quotient = numerator.div_euclid(denominator);
+    let twice_remainder = 2 * numerator.rem_euclid(denominator);
+    if twice_remainder > denominator || (twice_remainder == denominator && quotient % 2 != 0) {
+        quotient + 1
+    } else {
+        quotient
+    }
+}
+
+/// The CV scaling a device applies to 1V/oct pitch fields.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DeviceScale {
+    /// The ER-301 CV commands.
+    Er301,
+    /// The TXo CV and oscillator pitch commands.
+    Telexo,
+    /// The Just Friends pitch fields.
+    JustFriends,
+}
+
+impl DeviceScale {
+    /// Returns the raw value the device maps to 10V.
+    ///
+    /// All supported devices currently use the Teletype scaling, but keeping the value per
+    /// device keeps the constant in one place should a device diverge.
+    pub const fn cv_per_10_volts(self) -> i64 {
+        match self {
+            Self::Er301 | Self::Telexo | Self::JustFriends => CV_PER_10_VOLTS,
+        }
+    }
+}
+
+/// A musical pitch above 0V, stored in cents.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Pitch {
+    cents: i32,
+}
+
+impl Pitch {
+    /// Creates a pitch from a number of cents above 0V.
+    pub const fn from_cents(cents: i32) -> Self {
+        Self { cents }
+    }
+
+    /// Creates a pitch from a number of semitones above 0V.
+    pub const fn from_semitones(semitones: i32) -> Self {
+        Self::from_cents(semitones * 100)
+    }
+
+    /// Creates a pitch from an octave and a semitone within it, where octave 0 is 0V.
+    pub const fn from_octave(octave: i32, semitone: i32) -> Self {
+        Self::from_semitones(octave * 12 + semitone)
+    }
+
+    /// Returns the pitch in cents above 0V.
+    pub const fn cents(self) -> i32 {
+        self.cents
+    }
+
+    /// Renders the pitch as a raw CV value for the given device at 1V/oct.
+    ///
+    /// Rounds half to even and saturates at the limits of `i16`.
+    pub const fn for_device(self, scale: DeviceScale) -> i16 {
+        let cv = div_round_half_even(self.cents as i64 * scale.cv_per_10_volts(), 12000);
+        if cv > i16::MAX as i64 {
+            i16::MAX
+        } else if cv < i16::MIN as i64 {
+            i16::MIN
+        } else {
+            cv as i16
+        }
+    }
+}
@@ -0,0 +1,51 @@
+//! Append-style writing for payloads assembled from several commands.
+
+use crate::{Command, SerializationError};
+
+/// Writes commands one after another into a byte buffer, tracking the offset so callers
+/// need no hand-computed indices.
+///
+/// Every push checks the remaining space and leaves the rest of the buffer untouched if
+/// the command does not fit. The error counts the bytes already written, so `needed` is
+/// the offset the command would end at and `got` is the length of the whole buffer.
+/// Callers that know the full payload length should report that instead.
+pub(crate) struct Cursor<'a> {
+    buffer: &'a mut [u8],
+    position: usize,
+}
+
+impl<'a> Cursor<'a> {
+    /// Creates a cursor that writes from the start of `buffer`.
+    pub(crate) fn new(buffer: &'a mut [u8]) -> Self {
+        Self {
+            buffer,
+            position: 0,
+        }
+    }
+
+    /// Returns the number of bytes written so far.
+    pub(crate) fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Appends a whole command, laid out by its own [`Command::to_bytes`], so the cursor
+    /// never duplicates a device's wire format.
+    pub(crate) fn push_command<C: Command>(
+        &mut self,
+        command: &C,
+    ) -> Result<(), SerializationError> {
+        let end = self.position + command.len();
+        if end > self.buffer.len() {
+            return Err(SerializationError::buffer_too_small(end, self.buffer.len()));
+        }
+        let written = command.to_bytes(&mut self.buffer[self.position..])?.len();
+        self.position += written;
+        Ok(())
+    }
+
+    /// Returns the bytes written so far.
+    pub(crate) fn finish(self) -> &'a [u8] {
+        let Self { buffer, position } = self;
+        &buffer[..position]
+    }
+}
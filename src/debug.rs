@@ -0,0 +1,28 @@
+//! Helpers for inspecting serialized bytes, e.g. in logs.
+
+use core::fmt;
+
+/// Writes `bytes` as space-separated, two-digit uppercase hex, e.g. `11 05 20 00`.
+///
+/// This allocates nothing, so it works with any `core::fmt::Write`, such as a
+/// `heapless::String` or a UART writer:
+///
+/// ```
+/// use mii::{Command, debug, devices::er301};
+///
+/// let mut buffer = [0u8; er301::Commands::MAX_LENGTH];
+/// let bytes = er301::Commands::SetCv { port: 5, value: 8192 }.to_bytes(&mut buffer)?;
+/// let mut text = String::new();
+/// debug::write_hex(bytes, &mut text)?;
+/// assert_eq!(text, "11 05 20 00");
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub fn write_hex<W: fmt::Write>(bytes: &[u8], w: &mut W) -> fmt::Result {
+    for (i, byte) in bytes.iter().enumerate() {
+        if i > 0 {
+            w.write_char(' ')?;
+        }
+        write!(w, "{byte:02X}")?;
+    }
+    Ok(())
+}
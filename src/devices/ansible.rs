@@ -1,12 +1,62 @@
 //! Commands for the Monome Ansible module.
+//!
+//! All Ansible apps share one I2C address, so each app owns a block of opcodes:
+//! - `0x00`-`0x0F`: CV outputs
+//! - `0x10`-`0x1F`: trigger outputs
+//! - `0x20`-`0x2F`: presets
+//! - `0x30`-`0x3F`: Kria
+//! - `0x40`-`0x4F`: Meadowphysics
+//! - `0x50`-`0x5F`: Levels
+//! - `0x60`-`0x6F`: Cycles
+//!
+//! Checked constructors such as [`Commands::set_cv`] reject out-of-range ports and presets
+//! when the command is built, instead of when it is serialized.
+//!
+//! `RequestCurrentPreset` (opcode `0x22`) is the only command that Ansible answers. After
+//! writing it, read [`PRESET_RESPONSE_LENGTH`] bytes from the same address and decode them
+//! with [`parse_preset_response`]. The reply is a single byte holding the preset, 0-7.
 
-use crate::{Command, SerializationError};
+use core::fmt;
+
+use crate::{
+    BuildError, Command, CommandCategory, DeserializationError, DeviceDescriptor, Parse,
+    SerializationError, TargetKey, TeletypeOp, check_build_range, check_range, parse_bool,
+    put_i16_be, put_u16_be,
+};
 
 /// The fixed I2C address for Ansible.
 pub const ADDRESS: u8 = 0x20;
 
+/// Resolves the I2C address of the device with the given index.
+/// The address is fixed, so only index 0 is valid and any other index returns `None`.
+pub const fn address(index: u8) -> Option<u8> {
+    match index {
+        0 => Some(ADDRESS),
+        _ => None,
+    }
+}
+
+/// The number of bytes to read back after sending a `RequestCurrentPreset`.
+pub const PRESET_RESPONSE_LENGTH: usize = 1;
+
+/// Metadata describing the Ansible.
+pub const DESCRIPTOR: DeviceDescriptor = DeviceDescriptor {
+    name: "Ansible",
+    default_address: ADDRESS,
+    address_range: ADDRESS..=ADDRESS,
+    max_command_length: <Commands as Command>::MAX_LENGTH,
+    has_cv: true,
+    has_gates: true,
+    has_osc: false,
+    has_envelope: false,
+    cv_channels: 4,
+    gate_channels: 4,
+};
+
 /// All supported II commands for Ansible.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Commands {
     // --- CV Commands ---
     /// Sets the CV for a given output.
@@ -18,7 +68,8 @@ pub enum Commands {
     /// - `ms`: The slew time in milliseconds.
     SetCvSlew { port: u8, ms: u16 },
     /// A special mapping command, often used by grid/fader controllers.
-    /// `device_port` is a calculated value, typically `(fader_index / 4) << 1`.
+    /// `device_port` is a calculated value, typically `(fader_index / 4) << 1`
+    /// (see [`faderbank::device_port`](crate::devices::faderbank::device_port)).
     /// - `device_port`: The calculated target device sub-address.
     /// - `value`: An unsigned 16-bit fader value.
     SetCvFromFader { device_port: u8, value: u16 },
@@ -38,6 +89,13 @@ pub enum Commands {
     /// - `port`: 0-3
     /// - `ms`: The pulse duration in milliseconds.
     SetTrPulseDuration { port: u8, ms: u16 },
+    /// Sets the polarity of a trigger output, i.e. whether a pulse goes high or low.
+    /// - `port`: 0-3
+    /// - `polarity`: `true` for active high (1), `false` for inverted (0).
+    ///
+    /// Polarity is not honored by every app: it applies to triggers driven over II with the
+    /// commands above, while apps that generate their own triggers may ignore it.
+    SetTrPolarity { port: u8, polarity: bool },
 
     // --- App/Mode Commands ---
     /// Loads a preset.
@@ -46,93 +104,729 @@ pub enum Commands {
     /// Saves the current state to a preset.
     /// - `preset`: 0-7
     SavePreset { preset: u8 },
+    /// `ANS.PRESET`: Asks for the preset that was loaded last. Read
+    /// [`PRESET_RESPONSE_LENGTH`] bytes from the same address afterwards and decode them
+    /// with [`parse_preset_response`].
+    RequestCurrentPreset,
     /// In Kria, sets the step value. Not applicable to other apps.
     /// - `track`: 1-4
     /// - `step`: 0-15
     /// - `state`: 0=off, 1=on, 2=toggle
     KriaSetStep { track: u8, step: u8, state: u8 },
+    /// In Kria, sets the tempo of the internal clock.
+    /// - `bpm`: The tempo as an unsigned 16-bit integer.
+    KriaSetTempo { bpm: u16 },
+    /// In Kria, switches to another pattern.
+    /// - `pattern`: 0-15
+    KriaSelectPattern { pattern: u8 },
+    /// In Kria, mutes or unmutes a track.
+    /// - `track`: 1-4
+    /// - `state`: `true` to mute (1), `false` to unmute (0).
+    KriaMute { track: u8, state: bool },
+    /// In Kria, transposes the notes of a track.
+    /// - `track`: 1-4
+    /// - `semitones`: A signed 8-bit transposition in semitones.
+    KriaTranspose { track: u8, semitones: i8 },
+
+    // --- Meadowphysics Commands ---
+    /// In Meadowphysics, starts the internal clock.
+    MeadowphysicsStart,
+    /// In Meadowphysics, stops the internal clock.
+    MeadowphysicsStop,
+    /// In Meadowphysics, sets the speed of a row.
+    /// - `row`: 0-7
+    /// - `speed`: The row period in milliseconds as an unsigned 16-bit integer.
+    MeadowphysicsSetSpeed { row: u8, speed: u16 },
+    /// In Meadowphysics, resets a row to its starting count.
+    /// - `row`: 0-7
+    MeadowphysicsReset { row: u8 },
+
+    // --- Levels Commands ---
+    /// In Levels, sets the value of the current step on a track.
+    /// - `track`: 1-4
+    /// - `value`: A signed 16-bit integer representing voltage.
+    LevelsSetValue { track: u8, value: i16 },
+    /// In Levels, moves every track back to its first step.
+    LevelsReset,
+
+    // --- Cycles Commands ---
+    /// In Cycles, sets the frequency of a channel.
+    /// - `channel`: 0-3
+    /// - `freq`: The frequency as an unsigned 16-bit integer.
+    CyclesSetFreq { channel: u8, freq: u16 },
+    /// In Cycles, resets every channel to the start of its cycle.
+    CyclesReset,
 }
 
-impl Command for Commands {
-    const MAX_LENGTH: usize = 4; // Most commands are 1-4 bytes.
+/// Decodes the bytes read from Ansible after a `RequestCurrentPreset` into the preset.
+///
+/// Returns `DeserializationError::UnexpectedEof` if no byte was read and
+/// `DeserializationError::InvalidValue` if the preset is not 0-7. Any bytes after the
+/// response are ignored.
+pub fn parse_preset_response(bytes: &[u8]) -> Result<u8, DeserializationError> {
+    match *bytes {
+        [preset @ 0..=7, ..] => Ok(preset),
+        [_, ..] => Err(DeserializationError::InvalidValue),
+        [] => Err(DeserializationError::UnexpectedEof),
+    }
+}
 
-    fn to_bytes<'a>(&self, buffer: &'a mut [u8]) -> Result<&'a [u8], SerializationError> {
-        // A single check for the max required length simplifies the match arms.
-        if buffer.len() < Self::MAX_LENGTH {
-            return Err(SerializationError::BufferTooSmall);
+impl Commands {
+    /// Builds a `SetCv` command, checking that `port` is 0-3.
+    pub fn set_cv(port: u8, value: i16) -> Result<Self, BuildError> {
+        check_build_range("port", port, 0..=3)?;
+        Ok(Self::SetCv { port, value })
+    }
+
+    /// Builds a `SetCvSlew` command, checking that `port` is 0-3.
+    pub fn set_cv_slew(port: u8, ms: u16) -> Result<Self, BuildError> {
+        check_build_range("port", port, 0..=3)?;
+        Ok(Self::SetCvSlew { port, ms })
+    }
+
+    /// Builds a `SetTrState` command, checking that `port` is 0-3.
+    pub fn set_tr_state(port: u8, state: bool) -> Result<Self, BuildError> {
+        check_build_range("port", port, 0..=3)?;
+        Ok(Self::SetTrState { port, state })
+    }
+
+    /// Builds a `SetTrToggle` command, checking that `port` is 0-3.
+    pub fn set_tr_toggle(port: u8) -> Result<Self, BuildError> {
+        check_build_range("port", port, 0..=3)?;
+        Ok(Self::SetTrToggle { port })
+    }
+
+    /// Builds a `SetTrPulse` command, checking that `port` is 0-3.
+    pub fn set_tr_pulse(port: u8) -> Result<Self, BuildError> {
+        check_build_range("port", port, 0..=3)?;
+        Ok(Self::SetTrPulse { port })
+    }
+
+    /// Builds a `SetTrPulseDuration` command, checking that `port` is 0-3.
+    pub fn set_tr_pulse_duration(port: u8, ms: u16) -> Result<Self, BuildError> {
+        check_build_range("port", port, 0..=3)?;
+        Ok(Self::SetTrPulseDuration { port, ms })
+    }
+
+    /// Builds a `LoadPreset` command, checking that `preset` is 0-7.
+    pub fn load_preset(preset: u8) -> Result<Self, BuildError> {
+        check_build_range("preset", preset, 0..=7)?;
+        Ok(Self::LoadPreset { preset })
+    }
+
+    /// Builds a `SavePreset` command, checking that `preset` is 0-7.
+    pub fn save_preset(preset: u8) -> Result<Self, BuildError> {
+        check_build_range("preset", preset, 0..=7)?;
+        Ok(Self::SavePreset { preset })
+    }
+
+    /// Expands a bitmask into one `SetTrState` command per trigger output, where bit `n`
+    /// sets output `n`. Ansible has no native bitmask op, so this is done on the host and
+    /// costs one II write per output. Bits above 3 are ignored.
+    pub fn gates_from_mask(mask: u16) -> impl Iterator<Item = Self> {
+        (0..4).map(move |port| Self::SetTrState {
+            port,
+            state: mask & (1 << port) != 0,
+        })
+    }
+
+    /// Returns the opcode this command writes as its first byte.
+    pub const fn opcode(&self) -> u8 {
+        match self {
+            Self::SetCv { .. } => 0x01,
+            Self::SetCvSlew { .. } => 0x02,
+            Self::SetCvFromFader { .. } => 0x06,
+            Self::SetTrState { .. } => 0x10,
+            Self::SetTrToggle { .. } => 0x11,
+            Self::SetTrPulse { .. } => 0x12,
+            Self::SetTrPulseDuration { .. } => 0x13,
+            Self::SetTrPolarity { .. } => 0x14,
+            Self::LoadPreset { .. } => 0x20,
+            Self::SavePreset { .. } => 0x21,
+            Self::RequestCurrentPreset => 0x22,
+            Self::KriaSetStep { .. } => 0x30,
+            Self::KriaSetTempo { .. } => 0x31,
+            Self::KriaSelectPattern { .. } => 0x32,
+            Self::KriaMute { .. } => 0x33,
+            Self::KriaTranspose { .. } => 0x34,
+            Self::MeadowphysicsStart => 0x40,
+            Self::MeadowphysicsStop => 0x41,
+            Self::MeadowphysicsSetSpeed { .. } => 0x42,
+            Self::MeadowphysicsReset { .. } => 0x43,
+            Self::LevelsSetValue { .. } => 0x50,
+            Self::LevelsReset => 0x51,
+            Self::CyclesSetFreq { .. } => 0x60,
+            Self::CyclesReset => 0x61,
         }
+    }
 
+    /// Returns the category of the command, e.g. for prioritizing II traffic.
+    pub const fn category(&self) -> CommandCategory {
+        match self {
+            Self::SetCv { .. } | Self::SetCvSlew { .. } | Self::SetCvFromFader { .. } => {
+                CommandCategory::Cv
+            }
+            Self::SetTrState { .. }
+            | Self::SetTrToggle { .. }
+            | Self::SetTrPulse { .. }
+            | Self::SetTrPulseDuration { .. }
+            | Self::SetTrPolarity { .. } => CommandCategory::Gate,
+            Self::LoadPreset { .. } | Self::RequestCurrentPreset | Self::SavePreset { .. } => {
+                CommandCategory::Preset
+            }
+            Self::KriaSetStep { .. }
+            | Self::KriaSelectPattern { .. }
+            | Self::KriaMute { .. }
+            | Self::KriaTranspose { .. }
+            | Self::LevelsSetValue { .. } => CommandCategory::Mode,
+            Self::KriaSetTempo { .. }
+            | Self::MeadowphysicsStart
+            | Self::MeadowphysicsStop
+            | Self::MeadowphysicsSetSpeed { .. }
+            | Self::MeadowphysicsReset { .. }
+            | Self::LevelsReset
+            | Self::CyclesReset => CommandCategory::Transport,
+            Self::CyclesSetFreq { .. } => CommandCategory::Osc,
+        }
+    }
+
+    /// Returns the equivalent Teletype op. The outputs are Teletype's `CV` and `TR` 5-8,
+    /// and the app commands keep their `KR.*`, `MP.*`, `LV.*` and `CY.*` ops.
+    ///
+    /// Returns `None` for `SetCvFromFader`, the preset commands and
+    /// `RequestCurrentPreset`, which have no Teletype op.
+    pub const fn teletype_op(&self) -> Option<TeletypeOp<Self>> {
+        match self {
+            Self::SetCvFromFader { .. }
+            | Self::LoadPreset { .. }
+            | Self::SavePreset { .. }
+            | Self::RequestCurrentPreset => None,
+            _ => Some(TeletypeOp(*self)),
+        }
+    }
+
+    /// Returns the key identifying the destination the command writes to, see
+    /// [`TargetKey`].
+    ///
+    /// Returns `None` for toggles, pulses, preset commands, and transport or reset commands,
+    /// which act on every send.
+    pub const fn target_key(&self) -> Option<TargetKey> {
+        match *self {
+            Self::SetTrToggle { .. }
+            | Self::SetTrPulse { .. }
+            | Self::LoadPreset { .. }
+            | Self::SavePreset { .. }
+            | Self::RequestCurrentPreset
+            | Self::MeadowphysicsStart
+            | Self::MeadowphysicsStop
+            | Self::MeadowphysicsReset { .. }
+            | Self::LevelsReset
+            | Self::CyclesReset => None,
+            Self::SetCv { port, .. }
+            | Self::SetCvSlew { port, .. }
+            | Self::SetTrState { port, .. }
+            | Self::SetTrPulseDuration { port, .. }
+            | Self::SetTrPolarity { port, .. } => Some(TargetKey::new(self.opcode(), port)),
+            Self::SetCvFromFader { device_port, .. } => {
+                Some(TargetKey::new(self.opcode(), device_port))
+            }
+            Self::KriaSetStep { track, step, .. } => {
+                Some(TargetKey::with_index(self.opcode(), track, step))
+            }
+            Self::KriaMute { track, .. }
+            | Self::KriaTranspose { track, .. }
+            | Self::LevelsSetValue { track, .. } => Some(TargetKey::new(self.opcode(), track)),
+            Self::MeadowphysicsSetSpeed { row, .. } => Some(TargetKey::new(self.opcode(), row)),
+            Self::CyclesSetFreq { channel, .. } => Some(TargetKey::new(self.opcode(), channel)),
+            Self::KriaSetTempo { .. } | Self::KriaSelectPattern { .. } => {
+                Some(TargetKey::new(self.opcode(), 0))
+            }
+        }
+    }
+
+    /// Serializes the command into a fixed-size array, usable in `const` context.
+    ///
+    /// Returns the array and the number of valid bytes at its start. Unlike
+    /// [`Command::to_bytes`], this does not check field ranges.
+    pub const fn to_bytes_array(&self) -> ([u8; <Self as Command>::MAX_LENGTH], usize) {
+        let mut buffer = [0u8; <Self as Command>::MAX_LENGTH];
+        match self.encode(&mut buffer) {
+            Ok(len) => (buffer, len),
+            Err(_) => panic!("MAX_LENGTH is shorter than the command"),
+        }
+    }
+
+    /// Writes the command to the start of `buffer` and returns the number of bytes written.
+    const fn encode(&self, buffer: &mut [u8]) -> Result<usize, SerializationError> {
         match *self {
             // --- CV ---
             Self::SetCv { port, value } => {
-                let value_bytes = value.to_be_bytes();
-                buffer[0] = 0x01;
+                if buffer.len() < 4 {
+                    return Err(SerializationError::buffer_too_small(4, buffer.len()));
+                }
+                buffer[0] = self.opcode();
                 buffer[1] = port;
-                buffer[2] = value_bytes[0];
-                buffer[3] = value_bytes[1];
-                Ok(&buffer[..4])
+                put_i16_be(buffer, 2, value);
+                Ok(4)
             }
             Self::SetCvSlew { port, ms } => {
-                let ms_bytes = ms.to_be_bytes();
-                buffer[0] = 0x02;
+                if buffer.len() < 4 {
+                    return Err(SerializationError::buffer_too_small(4, buffer.len()));
+                }
+                buffer[0] = self.opcode();
                 buffer[1] = port;
-                buffer[2] = ms_bytes[0];
-                buffer[3] = ms_bytes[1];
-                Ok(&buffer[..4])
+                put_u16_be(buffer, 2, ms);
+                Ok(4)
             }
             Self::SetCvFromFader { device_port, value } => {
-                let value_bytes = value.to_be_bytes();
-                buffer[0] = 0x06;
+                if buffer.len() < 4 {
+                    return Err(SerializationError::buffer_too_small(4, buffer.len()));
+                }
+                buffer[0] = self.opcode();
                 buffer[1] = device_port;
-                buffer[2] = value_bytes[0];
-                buffer[3] = value_bytes[1];
-                Ok(&buffer[..4])
+                put_u16_be(buffer, 2, value);
+                Ok(4)
             }
 
             // --- Trigger ---
             Self::SetTrState { port, state } => {
-                buffer[0] = 0x10;
+                if buffer.len() < 3 {
+                    return Err(SerializationError::buffer_too_small(3, buffer.len()));
+                }
+                buffer[0] = self.opcode();
                 buffer[1] = port;
                 buffer[2] = state as u8;
-                Ok(&buffer[..3])
+                Ok(3)
             }
             Self::SetTrToggle { port } => {
-                buffer[0] = 0x11;
+                if buffer.len() < 2 {
+                    return Err(SerializationError::buffer_too_small(2, buffer.len()));
+                }
+                buffer[0] = self.opcode();
                 buffer[1] = port;
-                Ok(&buffer[..2])
+                Ok(2)
             }
             Self::SetTrPulse { port } => {
-                buffer[0] = 0x12;
+                if buffer.len() < 2 {
+                    return Err(SerializationError::buffer_too_small(2, buffer.len()));
+                }
+                buffer[0] = self.opcode();
                 buffer[1] = port;
-                Ok(&buffer[..2])
+                Ok(2)
             }
             Self::SetTrPulseDuration { port, ms } => {
-                let ms_bytes = ms.to_be_bytes();
-                buffer[0] = 0x13;
+                if buffer.len() < 4 {
+                    return Err(SerializationError::buffer_too_small(4, buffer.len()));
+                }
+                buffer[0] = self.opcode();
                 buffer[1] = port;
-                buffer[2] = ms_bytes[0];
-                buffer[3] = ms_bytes[1];
-                Ok(&buffer[..4])
+                put_u16_be(buffer, 2, ms);
+                Ok(4)
+            }
+            Self::SetTrPolarity { port, polarity } => {
+                if buffer.len() < 3 {
+                    return Err(SerializationError::buffer_too_small(3, buffer.len()));
+                }
+                buffer[0] = self.opcode();
+                buffer[1] = port;
+                buffer[2] = polarity as u8;
+                Ok(3)
             }
 
             // --- App/Mode ---
             Self::LoadPreset { preset } => {
-                buffer[0] = 0x20;
+                if buffer.len() < 2 {
+                    return Err(SerializationError::buffer_too_small(2, buffer.len()));
+                }
+                buffer[0] = self.opcode();
                 buffer[1] = preset;
-                Ok(&buffer[..2])
+                Ok(2)
             }
             Self::SavePreset { preset } => {
-                buffer[0] = 0x21;
+                if buffer.len() < 2 {
+                    return Err(SerializationError::buffer_too_small(2, buffer.len()));
+                }
+                buffer[0] = self.opcode();
                 buffer[1] = preset;
-                Ok(&buffer[..2])
+                Ok(2)
+            }
+            Self::RequestCurrentPreset => {
+                if buffer.is_empty() {
+                    return Err(SerializationError::buffer_too_small(1, 0));
+                }
+                buffer[0] = self.opcode();
+                Ok(1)
             }
             Self::KriaSetStep { track, step, state } => {
-                buffer[0] = 0x30;
+                if buffer.len() < 4 {
+                    return Err(SerializationError::buffer_too_small(4, buffer.len()));
+                }
+                buffer[0] = self.opcode();
                 buffer[1] = track;
                 buffer[2] = step;
                 buffer[3] = state;
-                Ok(&buffer[..4])
+                Ok(4)
             }
+            Self::KriaSetTempo { bpm } => {
+                if buffer.len() < 3 {
+                    return Err(SerializationError::buffer_too_small(3, buffer.len()));
+                }
+                buffer[0] = self.opcode();
+                put_u16_be(buffer, 1, bpm);
+                Ok(3)
+            }
+            Self::KriaSelectPattern { pattern } => {
+                if buffer.len() < 2 {
+                    return Err(SerializationError::buffer_too_small(2, buffer.len()));
+                }
+                buffer[0] = self.opcode();
+                buffer[1] = pattern;
+                Ok(2)
+            }
+            Self::KriaMute { track, state } => {
+                if buffer.len() < 3 {
+                    return Err(SerializationError::buffer_too_small(3, buffer.len()));
+                }
+                buffer[0] = self.opcode();
+                buffer[1] = track;
+                buffer[2] = state as u8;
+                Ok(3)
+            }
+            Self::KriaTranspose { track, semitones } => {
+                if buffer.len() < 3 {
+                    return Err(SerializationError::buffer_too_small(3, buffer.len()));
+                }
+                buffer[0] = self.opcode();
+                buffer[1] = track;
+                buffer[2] = semitones as u8;
+                Ok(3)
+            }
+
+            // --- Meadowphysics ---
+            Self::MeadowphysicsStart => {
+                if buffer.is_empty() {
+                    return Err(SerializationError::buffer_too_small(1, 0));
+                }
+                buffer[0] = self.opcode();
+                Ok(1)
+            }
+            Self::MeadowphysicsStop => {
+                if buffer.is_empty() {
+                    return Err(SerializationError::buffer_too_small(1, 0));
+                }
+                buffer[0] = self.opcode();
+                Ok(1)
+            }
+            Self::MeadowphysicsSetSpeed { row, speed } => {
+                if buffer.len() < 4 {
+                    return Err(SerializationError::buffer_too_small(4, buffer.len()));
+                }
+                buffer[0] = self.opcode();
+                buffer[1] = row;
+                put_u16_be(buffer, 2, speed);
+                Ok(4)
+            }
+            Self::MeadowphysicsReset { row } => {
+                if buffer.len() < 2 {
+                    return Err(SerializationError::buffer_too_small(2, buffer.len()));
+                }
+                buffer[0] = self.opcode();
+                buffer[1] = row;
+                Ok(2)
+            }
+
+            // --- Levels ---
+            Self::LevelsSetValue { track, value } => {
+                if buffer.len() < 4 {
+                    return Err(SerializationError::buffer_too_small(4, buffer.len()));
+                }
+                buffer[0] = self.opcode();
+                buffer[1] = track;
+                put_i16_be(buffer, 2, value);
+                Ok(4)
+            }
+            Self::LevelsReset => {
+                if buffer.is_empty() {
+                    return Err(SerializationError::buffer_too_small(1, 0));
+                }
+                buffer[0] = self.opcode();
+                Ok(1)
+            }
+
+            // --- Cycles ---
+            Self::CyclesSetFreq { channel, freq } => {
+                if buffer.len() < 4 {
+                    return Err(SerializationError::buffer_too_small(4, buffer.len()));
+                }
+                buffer[0] = self.opcode();
+                buffer[1] = channel;
+                put_u16_be(buffer, 2, freq);
+                Ok(4)
+            }
+            Self::CyclesReset => {
+                if buffer.is_empty() {
+                    return Err(SerializationError::buffer_too_small(1, 0));
+                }
+                buffer[0] = self.opcode();
+                Ok(1)
+            }
+        }
+    }
+
+    /// Checks the fields against the ranges documented on each variant.
+    fn validate(&self) -> Result<(), SerializationError> {
+        match *self {
+            Self::SetCv { port, .. }
+            | Self::SetCvSlew { port, .. }
+            | Self::SetTrState { port, .. }
+            | Self::SetTrToggle { port }
+            | Self::SetTrPulse { port }
+            | Self::SetTrPulseDuration { port, .. }
+            | Self::SetTrPolarity { port, .. } => check_range("port", port, 0..=3),
+            Self::LoadPreset { preset } | Self::SavePreset { preset } => {
+                check_range("preset", preset, 0..=7)
+            }
+            Self::KriaSetStep { track, step, state } => {
+                check_range("track", track, 1..=4)?;
+                check_range("step", step, 0..=15)?;
+                check_range("state", state, 0..=2)
+            }
+            Self::KriaSelectPattern { pattern } => check_range("pattern", pattern, 0..=15),
+            Self::KriaMute { track, .. }
+            | Self::KriaTranspose { track, .. }
+            | Self::LevelsSetValue { track, .. } => check_range("track", track, 1..=4),
+            Self::MeadowphysicsSetSpeed { row, .. } | Self::MeadowphysicsReset { row } => {
+                check_range("row", row, 0..=7)
+            }
+            Self::CyclesSetFreq { channel, .. } => check_range("channel", channel, 0..=3),
+            Self::SetCvFromFader { .. }
+            | Self::RequestCurrentPreset
+            | Self::KriaSetTempo { .. }
+            | Self::MeadowphysicsStart
+            | Self::MeadowphysicsStop
+            | Self::LevelsReset
+            | Self::CyclesReset => Ok(()),
+        }
+    }
+}
+
+/// Formats the command as a Teletype-style mnemonic followed by its fields in declaration
+/// order, separated by spaces. Booleans are written as 0 or 1.
+///
+/// The mnemonics are stable, so tools may parse them from logs:
+///
+/// | Command | Mnemonic |
+/// |---------|----------|
+/// | `SetCv` | `ANS.CV` |
+/// | `SetCvSlew` | `ANS.CV.SLEW` |
+/// | `SetCvFromFader` | `ANS.CV.FADER` |
+/// | `SetTrState` | `ANS.TR` |
+/// | `SetTrToggle` | `ANS.TR.TOG` |
+/// | `SetTrPulse` | `ANS.TR.PULSE` |
+/// | `SetTrPulseDuration` | `ANS.TR.TIME` |
+/// | `SetTrPolarity` | `ANS.TR.POL` |
+/// | `LoadPreset` | `ANS.PRESET.LOAD` |
+/// | `SavePreset` | `ANS.PRESET.SAVE` |
+/// | `RequestCurrentPreset` | `ANS.PRESET` |
+/// | `KriaSetStep` | `KR.STEP` |
+/// | `KriaSetTempo` | `KR.TEMPO` |
+/// | `KriaSelectPattern` | `KR.PAT` |
+/// | `KriaMute` | `KR.MUTE` |
+/// | `KriaTranspose` | `KR.TRANS` |
+/// | `MeadowphysicsStart` | `MP.START` |
+/// | `MeadowphysicsStop` | `MP.STOP` |
+/// | `MeadowphysicsSetSpeed` | `MP.SPEED` |
+/// | `MeadowphysicsReset` | `MP.RESET` |
+/// | `LevelsSetValue` | `LV.SET` |
+/// | `LevelsReset` | `LV.RESET` |
+/// | `CyclesSetFreq` | `CY.FREQ` |
+/// | `CyclesReset` | `CY.RESET` |
+impl fmt::Display for Commands {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::SetCv { port, value } => write!(f, "ANS.CV {port} {value}"),
+            Self::SetCvSlew { port, ms } => write!(f, "ANS.CV.SLEW {port} {ms}"),
+            Self::SetCvFromFader { device_port, value } => {
+                write!(f, "ANS.CV.FADER {device_port} {value}")
+            }
+            Self::SetTrState { port, state } => write!(f, "ANS.TR {port} {}", u8::from(state)),
+            Self::SetTrToggle { port } => write!(f, "ANS.TR.TOG {port}"),
+            Self::SetTrPulse { port } => write!(f, "ANS.TR.PULSE {port}"),
+            Self::SetTrPulseDuration { port, ms } => write!(f, "ANS.TR.TIME {port} {ms}"),
+            Self::SetTrPolarity { port, polarity } => {
+                write!(f, "ANS.TR.POL {port} {}", u8::from(polarity))
+            }
+            Self::LoadPreset { preset } => write!(f, "ANS.PRESET.LOAD {preset}"),
+            Self::SavePreset { preset } => write!(f, "ANS.PRESET.SAVE {preset}"),
+            Self::RequestCurrentPreset => f.write_str("ANS.PRESET"),
+            Self::KriaSetStep { track, step, state } => write!(f, "KR.STEP {track} {step} {state}"),
+            Self::KriaSetTempo { bpm } => write!(f, "KR.TEMPO {bpm}"),
+            Self::KriaSelectPattern { pattern } => write!(f, "KR.PAT {pattern}"),
+            Self::KriaMute { track, state } => write!(f, "KR.MUTE {track} {}", u8::from(state)),
+            Self::KriaTranspose { track, semitones } => write!(f, "KR.TRANS {track} {semitones}"),
+            Self::MeadowphysicsStart => f.write_str("MP.START"),
+            Self::MeadowphysicsStop => f.write_str("MP.STOP"),
+            Self::MeadowphysicsSetSpeed { row, speed } => write!(f, "MP.SPEED {row} {speed}"),
+            Self::MeadowphysicsReset { row } => write!(f, "MP.RESET {row}"),
+            Self::LevelsSetValue { track, value } => write!(f, "LV.SET {track} {value}"),
+            Self::LevelsReset => f.write_str("LV.RESET"),
+            Self::CyclesSetFreq { channel, freq } => write!(f, "CY.FREQ {channel} {freq}"),
+            Self::CyclesReset => f.write_str("CY.RESET"),
         }
     }
 }
+
+/// Formats the command as its Teletype op, where the first Ansible's outputs are `CV` and
+/// `TR` 5-8.
+impl fmt::Display for TeletypeOp<Commands> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Commands::SetCv { port, value } => write!(f, "CV {} {value}", u16::from(port) + 5),
+            Commands::SetCvSlew { port, ms } => write!(f, "CV.SLEW {} {ms}", u16::from(port) + 5),
+            Commands::SetTrState { port, state } => {
+                write!(f, "TR {} {}", u16::from(port) + 5, u8::from(state))
+            }
+            Commands::SetTrToggle { port } => write!(f, "TR.TOG {}", u16::from(port) + 5),
+            Commands::SetTrPulse { port } => write!(f, "TR.PULSE {}", u16::from(port) + 5),
+            Commands::SetTrPulseDuration { port, ms } => {
+                write!(f, "TR.TIME {} {ms}", u16::from(port) + 5)
+            }
+            Commands::SetTrPolarity { port, polarity } => {
+                write!(f, "TR.POL {} {}", u16::from(port) + 5, u8::from(polarity))
+            }
+            command => fmt::Display::fmt(&command, f),
+        }
+    }
+}
+
+impl Command for Commands {
+    const MAX_LENGTH: usize = 4; // Most commands are 1-4 bytes.
+
+    fn to_bytes<'a>(&self, buffer: &'a mut [u8]) -> Result<&'a [u8], SerializationError> {
+        #[cfg(feature = "strict")]
+        self.validate()?;
+        #[cfg(not(feature = "strict"))]
+        debug_assert!(self.validate().is_ok(), "field out of its documented range");
+
+        let len = self.encode(buffer)?;
+        Ok(&buffer[..len])
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            Self::MeadowphysicsStart
+            | Self::MeadowphysicsStop
+            | Self::LevelsReset
+            | Self::CyclesReset
+            | Self::RequestCurrentPreset => 1,
+            Self::SetTrToggle { .. }
+            | Self::SetTrPulse { .. }
+            | Self::LoadPreset { .. }
+            | Self::SavePreset { .. }
+            | Self::KriaSelectPattern { .. }
+            | Self::MeadowphysicsReset { .. } => 2,
+            Self::SetTrState { .. }
+            | Self::KriaSetTempo { .. }
+            | Self::KriaMute { .. }
+            | Self::KriaTranspose { .. }
+            | Self::SetTrPolarity { .. } => 3,
+            Self::SetCv { .. }
+            | Self::SetCvSlew { .. }
+            | Self::SetCvFromFader { .. }
+            | Self::SetTrPulseDuration { .. }
+            | Self::KriaSetStep { .. }
+            | Self::MeadowphysicsSetSpeed { .. }
+            | Self::LevelsSetValue { .. }
+            | Self::CyclesSetFreq { .. } => 4,
+        }
+    }
+
+    fn address(&self, _index: u8) -> Option<u8> {
+        Some(ADDRESS)
+    }
+}
+
+impl Parse for Commands {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, usize), DeserializationError> {
+        let (&opcode, payload) = bytes
+            .split_first()
+            .ok_or(DeserializationError::UnexpectedEof)?;
+        let command = match (opcode, payload) {
+            (0x01, &[port, hi, lo, ..]) => Self::SetCv {
+                port,
+                value: i16::from_be_bytes([hi, lo]),
+            },
+            (0x02, &[port, hi, lo, ..]) => Self::SetCvSlew {
+                port,
+                ms: u16::from_be_bytes([hi, lo]),
+            },
+            (0x06, &[device_port, hi, lo, ..]) => Self::SetCvFromFader {
+                device_port,
+                value: u16::from_be_bytes([hi, lo]),
+            },
+            (0x10, &[port, state, ..]) => Self::SetTrState {
+                port,
+                state: parse_bool(state)?,
+            },
+            (0x11, &[port, ..]) => Self::SetTrToggle { port },
+            (0x12, &[port, ..]) => Self::SetTrPulse { port },
+            (0x13, &[port, hi, lo, ..]) => Self::SetTrPulseDuration {
+                port,
+                ms: u16::from_be_bytes([hi, lo]),
+            },
+            (0x14, &[port, polarity, ..]) => Self::SetTrPolarity {
+                port,
+                polarity: parse_bool(polarity)?,
+            },
+            (0x20, &[preset, ..]) => Self::LoadPreset { preset },
+            (0x21, &[preset, ..]) => Self::SavePreset { preset },
+            (0x22, _) => Self::RequestCurrentPreset,
+            (0x30, &[track, step, state, ..]) => Self::KriaSetStep { track, step, state },
+            (0x31, &[hi, lo, ..]) => Self::KriaSetTempo {
+                bpm: u16::from_be_bytes([hi, lo]),
+            },
+            (0x32, &[pattern, ..]) => Self::KriaSelectPattern { pattern },
+            (0x33, &[track, state, ..]) => Self::KriaMute {
+                track,
+                state: parse_bool(state)?,
+            },
+            (0x34, &[track, semitones, ..]) => Self::KriaTranspose {
+                track,
+                semitones: semitones as i8,
+            },
+            (0x40, _) => Self::MeadowphysicsStart,
+            (0x41, _) => Self::MeadowphysicsStop,
+            (0x42, &[row, hi, lo, ..]) => Self::MeadowphysicsSetSpeed {
+                row,
+                speed: u16::from_be_bytes([hi, lo]),
+            },
+            (0x43, &[row, ..]) => Self::MeadowphysicsReset { row },
+            (0x50, &[track, hi, lo, ..]) => Self::LevelsSetValue {
+                track,
+                value: i16::from_be_bytes([hi, lo]),
+            },
+            (0x51, _) => Self::LevelsReset,
+            (0x60, &[channel, hi, lo, ..]) => Self::CyclesSetFreq {
+                channel,
+                freq: u16::from_be_bytes([hi, lo]),
+            },
+            (0x61, _) => Self::CyclesReset,
+            (
+                0x01
+                | 0x02
+                | 0x06
+                | 0x10..=0x14
+                | 0x20
+                | 0x21
+                | 0x30..=0x34
+                | 0x42
+                | 0x43
+                | 0x50
+                | 0x60,
+                _,
+            ) => return Err(DeserializationError::UnexpectedEof),
+            (opcode, _) => return Err(DeserializationError::UnknownOpcode(opcode)),
+        };
+        Ok((command, command.len()))
+    }
+}
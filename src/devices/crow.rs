@@ -0,0 +1,259 @@
+//! Commands for the Monome Crow, acting as an II follower.
+//!
+//! Crow answers on `ADDRESS` by default, but a Lua script running on Crow can reassign it
+//! to `0x02`-`0x04`, the addresses Teletype reaches as `CROW2`-`CROW4`. This crate only
+//! serializes messages, so if you have changed it, pass your configured address to your
+//! I2C write call instead of `ADDRESS`.
+//!
+//! `Query` (opcode `0x03`) is the only command that Crow answers. After writing it, read
+//! [`RESPONSE_LENGTH`] bytes from the same address and decode them with
+//! [`parse_response`]. The reply carries no opcode of its own; it echoes the query index
+//! followed by the value.
+
+use core::fmt;
+
+use crate::{
+    Command, CommandCategory, DeserializationError, DeviceDescriptor, Parse, SerializationError,
+    TargetKey, TeletypeOp, check_range, put_i16_be,
+};
+
+/// The default I2C address for Crow. Reassigned addresses go up to `0x04`.
+pub const ADDRESS: u8 = 0x01;
+
+/// Resolves the I2C address of the device with the given index.
+/// Only index 0 is valid, for the default address, and any other index returns `None`.
+/// A Crow reassigned from Lua is addressed directly, see the module docs.
+pub const fn address(index: u8) -> Option<u8> {
+    match index {
+        0 => Some(ADDRESS),
+        _ => None,
+    }
+}
+
+/// The number of bytes to read back after sending a `Query`.
+pub const RESPONSE_LENGTH: usize = 3;
+
+/// Metadata describing the Crow.
+pub const DESCRIPTOR: DeviceDescriptor = DeviceDescriptor {
+    name: "Crow",
+    default_address: ADDRESS,
+    address_range: ADDRESS..=0x04,
+    max_command_length: <Commands as Command>::MAX_LENGTH,
+    has_cv: true,
+    has_gates: false,
+    has_osc: false,
+    has_envelope: false,
+    cv_channels: 4,
+    gate_channels: 0,
+};
+
+/// All supported II commands for Crow.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Commands {
+    /// `CROW.V`: Sets the voltage of an output.
+    /// - `channel`: 1-4
+    /// - `value`: A signed 16-bit integer representing voltage.
+    SetOutput { channel: u8, value: i16 },
+    /// `CROW.C`: Calls one of the `ii.self.call` handlers defined in the Crow script.
+    /// - `index`: 1-4, selecting `call1` to `call4`.
+    /// - `arg`: A signed 16-bit argument passed to the handler.
+    CallFunction { index: u8, arg: i16 },
+    /// `CROW.Q`: Asks Crow to run one of its `ii.self.query` handlers. The result is
+    /// returned by a subsequent I2C read, see [`parse_response`].
+    /// - `index`: 0-3, selecting `query0` to `query3`.
+    Query { index: u8 },
+}
+
+/// The reply Crow sends to a `Query`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct QueryResponse {
+    /// The index of the query handler that answered, 0-3.
+    pub index: u8,
+    /// The signed 16-bit value returned by the handler.
+    pub value: i16,
+}
+
+/// Decodes the bytes read from Crow after a `Query`.
+///
+/// Returns `DeserializationError::UnexpectedEof` if fewer than [`RESPONSE_LENGTH`] bytes
+/// were read. Any bytes after the response are ignored.
+pub fn parse_response(bytes: &[u8]) -> Result<QueryResponse, DeserializationError> {
+    match *bytes {
+        [index, hi, lo, ..] => Ok(QueryResponse {
+            index,
+            value: i16::from_be_bytes([hi, lo]),
+        }),
+        _ => Err(DeserializationError::UnexpectedEof),
+    }
+}
+
+impl Commands {
+    /// Returns the opcode this command writes as its first byte.
+    pub const fn opcode(&self) -> u8 {
+        match self {
+            Self::SetOutput { .. } => 0x01,
+            Self::CallFunction { .. } => 0x02,
+            Self::Query { .. } => 0x03,
+        }
+    }
+
+    /// Returns the category of the command, e.g. for prioritizing II traffic.
+    pub const fn category(&self) -> CommandCategory {
+        match self {
+            Self::SetOutput { .. } => CommandCategory::Cv,
+            Self::CallFunction { .. } | Self::Query { .. } => CommandCategory::Mode,
+        }
+    }
+
+    /// Returns the equivalent Teletype `CROW.*` op. Every command has one.
+    pub const fn teletype_op(&self) -> Option<TeletypeOp<Self>> {
+        Some(TeletypeOp(*self))
+    }
+
+    /// Returns the key identifying the destination the command writes to, see
+    /// [`TargetKey`].
+    ///
+    /// Returns `None` for function calls and queries, which act on every send.
+    pub const fn target_key(&self) -> Option<TargetKey> {
+        match *self {
+            Self::SetOutput { channel, .. } => Some(TargetKey::new(self.opcode(), channel)),
+            Self::CallFunction { .. } | Self::Query { .. } => None,
+        }
+    }
+
+    /// Serializes the command into a fixed-size array, usable in `const` context.
+    ///
+    /// Returns the array and the number of valid bytes at its start. Unlike
+    /// [`Command::to_bytes`], this does not check field ranges.
+    pub const fn to_bytes_array(&self) -> ([u8; <Self as Command>::MAX_LENGTH], usize) {
+        let mut buffer = [0u8; <Self as Command>::MAX_LENGTH];
+        match self.encode(&mut buffer) {
+            Ok(len) => (buffer, len),
+            Err(_) => panic!("MAX_LENGTH is shorter than the command"),
+        }
+    }
+
+    /// Writes the command to the start of `buffer` and returns the number of bytes written.
+    const fn encode(&self, buffer: &mut [u8]) -> Result<usize, SerializationError> {
+        match *self {
+            Self::SetOutput { channel, value } => {
+                if buffer.len() < 4 {
+                    return Err(SerializationError::buffer_too_small(4, buffer.len()));
+                }
+                buffer[0] = self.opcode();
+                buffer[1] = channel;
+                put_i16_be(buffer, 2, value);
+                Ok(4)
+            }
+            Self::CallFunction { index, arg } => {
+                if buffer.len() < 4 {
+                    return Err(SerializationError::buffer_too_small(4, buffer.len()));
+                }
+                buffer[0] = self.opcode();
+                buffer[1] = index;
+                put_i16_be(buffer, 2, arg);
+                Ok(4)
+            }
+            Self::Query { index } => {
+                if buffer.len() < 2 {
+                    return Err(SerializationError::buffer_too_small(2, buffer.len()));
+                }
+                buffer[0] = self.opcode();
+                buffer[1] = index;
+                Ok(2)
+            }
+        }
+    }
+
+    /// Checks the fields against the ranges documented on each variant.
+    fn validate(&self) -> Result<(), SerializationError> {
+        match *self {
+            Self::SetOutput { channel, .. } => check_range("channel", channel, 1..=4),
+            Self::CallFunction { index, .. } => check_range("index", index, 1..=4),
+            Self::Query { index } => check_range("index", index, 0..=3),
+        }
+    }
+}
+
+/// Formats the command as a Teletype-style mnemonic followed by its fields in declaration
+/// order, separated by spaces. Booleans are written as 0 or 1.
+///
+/// The mnemonics are stable, so tools may parse them from logs:
+///
+/// | Command | Mnemonic |
+/// |---------|----------|
+/// | `SetOutput` | `CROW.V` |
+/// | `CallFunction` | `CROW.C` |
+/// | `Query` | `CROW.Q` |
+impl fmt::Display for Commands {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::SetOutput { channel, value } => write!(f, "CROW.V {channel} {value}"),
+            Self::CallFunction { index, arg } => write!(f, "CROW.C {index} {arg}"),
+            Self::Query { index } => write!(f, "CROW.Q {index}"),
+        }
+    }
+}
+
+/// Formats the command as its Teletype `CROW.*` op. Teletype has one op per call and
+/// query handler, e.g. `CROW.C2` and `CROW.Q0`.
+impl fmt::Display for TeletypeOp<Commands> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Commands::CallFunction { index, arg } => write!(f, "CROW.C{index} {arg}"),
+            Commands::Query { index } => write!(f, "CROW.Q{index}"),
+            command => fmt::Display::fmt(&command, f),
+        }
+    }
+}
+
+impl Command for Commands {
+    const MAX_LENGTH: usize = 4; // SetOutput and CallFunction are the longest.
+
+    fn to_bytes<'a>(&self, buffer: &'a mut [u8]) -> Result<&'a [u8], SerializationError> {
+        #[cfg(feature = "strict")]
+        self.validate()?;
+        #[cfg(not(feature = "strict"))]
+        debug_assert!(self.validate().is_ok(), "field out of its documented range");
+
+        let len = self.encode(buffer)?;
+        Ok(&buffer[..len])
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            Self::Query { .. } => 2,
+            Self::SetOutput { .. } | Self::CallFunction { .. } => 4,
+        }
+    }
+
+    fn address(&self, _index: u8) -> Option<u8> {
+        Some(ADDRESS)
+    }
+}
+
+impl Parse for Commands {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, usize), DeserializationError> {
+        let (&opcode, payload) = bytes
+            .split_first()
+            .ok_or(DeserializationError::UnexpectedEof)?;
+        let command = match (opcode, payload) {
+            (0x01, &[channel, hi, lo, ..]) => Self::SetOutput {
+                channel,
+                value: i16::from_be_bytes([hi, lo]),
+            },
+            (0x02, &[index, hi, lo, ..]) => Self::CallFunction {
+                index,
+                arg: i16::from_be_bytes([hi, lo]),
+            },
+            (0x03, &[index, ..]) => Self::Query { index },
+            (0x01..=0x03, _) => return Err(DeserializationError::UnexpectedEof),
+            (opcode, _) => return Err(DeserializationError::UnknownOpcode(opcode)),
+        };
+        Ok((command, command.len()))
+    }
+}
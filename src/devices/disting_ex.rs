@@ -0,0 +1,354 @@
+//! Commands for the Expert Sleepers Disting EX.
+//! The Disting EX can be configured to answer on `0x41`-`0x44`, so rigs with several
+//! units should send to the address chosen in each unit's settings.
+//!
+//! `LoadPreset`, `SavePreset` and `SelectSdFile` access the SD card and can take the
+//! Disting EX a noticeable time to complete, so space them out from the commands that
+//! follow rather than sending them back to back in one burst.
+//!
+//! `NoteOn` and `NoteOff` play the Disting EX as a polyphonic voice and only take
+//! effect in its polyphonic algorithms. The device allocates voices itself: each note on
+//! takes a free voice, or steals the oldest one when all are busy, and a note off
+//! releases whichever voice is playing that note. Notes are not tied to a MIDI channel,
+//! so one II address drives one polyphonic algorithm.
+//!
+//! II messages to the Disting EX are unframed: unlike its SysEx interface, they carry
+//! no checksum or length byte, so `to_bytes` writes the opcode and fields only.
+
+use core::fmt;
+
+use crate::{
+    Command, CommandCategory, DeserializationError, DeviceDescriptor, Parse, SerializationError,
+    TargetKey, TeletypeOp, check_range, put_i16_be,
+};
+
+/// The default I2C address for the Disting EX. Alternate addresses go up to `0x44`.
+pub const ADDRESS: u8 = 0x41;
+
+/// Resolves the I2C address of the device with the given index.
+/// - `index`: 0-3, for addresses `0x41` to `0x44`.
+///
+/// Returns `None` if `index` is out of range.
+pub const fn address(index: u8) -> Option<u8> {
+    if index <= 3 {
+        Some(ADDRESS + index)
+    } else {
+        None
+    }
+}
+
+/// Metadata describing the Disting EX.
+pub const DESCRIPTOR: DeviceDescriptor = DeviceDescriptor {
+    name: "Disting EX",
+    default_address: ADDRESS,
+    address_range: ADDRESS..=0x44,
+    max_command_length: <Commands as Command>::MAX_LENGTH,
+    has_cv: false,
+    has_gates: false,
+    has_osc: false,
+    has_envelope: false,
+    cv_channels: 0,
+    gate_channels: 0,
+};
+
+/// All supported II commands for the Disting EX.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Commands {
+    /// `select algorithm`: Switches the loaded algorithm.
+    /// - `index`: The algorithm index as listed in the Disting EX manual.
+    SelectAlgorithm { index: u8 },
+    /// `set parameter`: Sets a parameter of the current algorithm.
+    /// - `param`: The parameter index.
+    /// - `value`: A signed 16-bit parameter value.
+    SetParameter { param: u8, value: i16 },
+    /// `play sample`: Triggers playback of a sample in the SD playback algorithms.
+    /// - `slot`: The sample slot to trigger.
+    PlaySample { slot: u8 },
+    /// `set Z mode parameter`: Sets the parameter controlled by the Z knob.
+    /// - `param`: The parameter index.
+    /// - `value`: A signed 16-bit parameter value.
+    SetZModeParameter { param: u8, value: i16 },
+    /// `EX.PRESET`: Loads a preset from the SD card.
+    /// - `index`: 0-255, the preset number as listed on the SD card.
+    LoadPreset { index: u8 },
+    /// `EX.SAVE`: Saves the current setup as a preset on the SD card.
+    /// - `index`: 0-255, the preset number to write.
+    SavePreset { index: u8 },
+    /// `select SD file`: Selects a file for the SD playback algorithms.
+    /// - `folder`: 0-255, the folder index in the order the algorithm lists them.
+    /// - `file`: 0-255, the file index within the folder.
+    SelectSdFile { folder: u8, file: u8 },
+    /// `note on`: Starts a note in the polyphonic algorithms, which pick the voice to play it.
+    /// - `note`: 0-127, the MIDI note number.
+    /// - `velocity`: 0-127, the MIDI velocity, sent as a single byte.
+    NoteOn { note: u8, velocity: u8 },
+    /// `note off`: Releases the voice playing a note.
+    /// - `note`: 0-127, the MIDI note number of the note to release.
+    NoteOff { note: u8 },
+}
+
+impl Commands {
+    /// Returns the opcode this command writes as its first byte.
+    pub const fn opcode(&self) -> u8 {
+        match self {
+            Self::SelectAlgorithm { .. } => 0x44,
+            Self::SetParameter { .. } => 0x46,
+            Self::PlaySample { .. } => 0x4D,
+            Self::SetZModeParameter { .. } => 0x4A,
+            Self::LoadPreset { .. } => 0x40,
+            Self::SavePreset { .. } => 0x41,
+            Self::SelectSdFile { .. } => 0x4B,
+            Self::NoteOn { .. } => 0x55,
+            Self::NoteOff { .. } => 0x56,
+        }
+    }
+
+    /// Returns the category of the command, e.g. for prioritizing II traffic.
+    pub const fn category(&self) -> CommandCategory {
+        match self {
+            Self::PlaySample { .. } | Self::NoteOff { .. } | Self::NoteOn { .. } => {
+                CommandCategory::Gate
+            }
+            Self::SelectAlgorithm { .. } | Self::SelectSdFile { .. } => CommandCategory::Preset,
+            Self::SetParameter { .. } | Self::SetZModeParameter { .. } => CommandCategory::Mode,
+            Self::LoadPreset { .. } | Self::SavePreset { .. } => CommandCategory::Preset,
+        }
+    }
+
+    /// Returns the equivalent Teletype `EX.*` op.
+    ///
+    /// Returns `None` for `SelectSdFile`, which has no Teletype op, and for `NoteOn` and
+    /// `NoteOff`, since Teletype's `EX.NOTE` ops take a pitch CV rather than a MIDI note.
+    pub const fn teletype_op(&self) -> Option<TeletypeOp<Self>> {
+        match self {
+            Self::SelectSdFile { .. } | Self::NoteOn { .. } | Self::NoteOff { .. } => None,
+            _ => Some(TeletypeOp(*self)),
+        }
+    }
+
+    /// Returns the key identifying the destination the command writes to, see
+    /// [`TargetKey`].
+    ///
+    /// Returns `None` for `PlaySample`, the note commands and the preset loads and saves,
+    /// which act on every send.
+    pub const fn target_key(&self) -> Option<TargetKey> {
+        match *self {
+            Self::SelectAlgorithm { .. } => Some(TargetKey::new(self.opcode(), 0)),
+            Self::SetParameter { param, .. } | Self::SetZModeParameter { param, .. } => {
+                Some(TargetKey::new(self.opcode(), param))
+            }
+            Self::SelectSdFile { .. } => Some(TargetKey::new(self.opcode(), 0)),
+            Self::PlaySample { .. }
+            | Self::LoadPreset { .. }
+            | Self::SavePreset { .. }
+            | Self::NoteOn { .. }
+            | Self::NoteOff { .. } => None,
+        }
+    }
+
+    /// Serializes the command into a fixed-size array, usable in `const` context.
+    ///
+    /// Returns the array and the number of valid bytes at its start. Unlike
+    /// [`Command::to_bytes`], this does not check field ranges.
+    pub const fn to_bytes_array(&self) -> ([u8; <Self as Command>::MAX_LENGTH], usize) {
+        let mut buffer = [0u8; <Self as Command>::MAX_LENGTH];
+        match self.encode(&mut buffer) {
+            Ok(len) => (buffer, len),
+            Err(_) => panic!("MAX_LENGTH is shorter than the command"),
+        }
+    }
+
+    /// Writes the command to the start of `buffer` and returns the number of bytes written.
+    const fn encode(&self, buffer: &mut [u8]) -> Result<usize, SerializationError> {
+        match *self {
+            Self::SelectAlgorithm { index } => {
+                if buffer.len() < 2 {
+                    return Err(SerializationError::buffer_too_small(2, buffer.len()));
+                }
+                buffer[0] = self.opcode();
+                buffer[1] = index;
+                Ok(2)
+            }
+            Self::SetParameter { param, value } => {
+                if buffer.len() < 4 {
+                    return Err(SerializationError::buffer_too_small(4, buffer.len()));
+                }
+                buffer[0] = self.opcode();
+                buffer[1] = param;
+                put_i16_be(buffer, 2, value);
+                Ok(4)
+            }
+            Self::PlaySample { slot } => {
+                if buffer.len() < 2 {
+                    return Err(SerializationError::buffer_too_small(2, buffer.len()));
+                }
+                buffer[0] = self.opcode();
+                buffer[1] = slot;
+                Ok(2)
+            }
+            Self::SetZModeParameter { param, value } => {
+                if buffer.len() < 4 {
+                    return Err(SerializationError::buffer_too_small(4, buffer.len()));
+                }
+                buffer[0] = self.opcode();
+                buffer[1] = param;
+                put_i16_be(buffer, 2, value);
+                Ok(4)
+            }
+            Self::LoadPreset { index } | Self::SavePreset { index } => {
+                if buffer.len() < 2 {
+                    return Err(SerializationError::buffer_too_small(2, buffer.len()));
+                }
+                buffer[0] = self.opcode();
+                buffer[1] = index;
+                Ok(2)
+            }
+            Self::SelectSdFile { folder, file } => {
+                if buffer.len() < 3 {
+                    return Err(SerializationError::buffer_too_small(3, buffer.len()));
+                }
+                buffer[0] = self.opcode();
+                buffer[1] = folder;
+                buffer[2] = file;
+                Ok(3)
+            }
+            Self::NoteOn { note, velocity } => {
+                if buffer.len() < 3 {
+                    return Err(SerializationError::buffer_too_small(3, buffer.len()));
+                }
+                buffer[0] = self.opcode();
+                buffer[1] = note;
+                buffer[2] = velocity;
+                Ok(3)
+            }
+            Self::NoteOff { note } => {
+                if buffer.len() < 2 {
+                    return Err(SerializationError::buffer_too_small(2, buffer.len()));
+                }
+                buffer[0] = self.opcode();
+                buffer[1] = note;
+                Ok(2)
+            }
+        }
+    }
+
+    /// Checks the fields against the ranges documented on each variant.
+    fn validate(&self) -> Result<(), SerializationError> {
+        match *self {
+            Self::NoteOn { note, velocity } => {
+                check_range("note", note, 0..=127)?;
+                check_range("velocity", velocity, 0..=127)
+            }
+            Self::NoteOff { note } => check_range("note", note, 0..=127),
+            Self::SelectAlgorithm { .. }
+            | Self::SetParameter { .. }
+            | Self::PlaySample { .. }
+            | Self::SetZModeParameter { .. }
+            | Self::LoadPreset { .. }
+            | Self::SavePreset { .. }
+            | Self::SelectSdFile { .. } => Ok(()),
+        }
+    }
+}
+
+/// Formats the command as a Teletype-style mnemonic followed by its fields in declaration
+/// order, separated by spaces. Booleans are written as 0 or 1.
+///
+/// The mnemonics are stable, so tools may parse them from logs:
+///
+/// | Command | Mnemonic |
+/// |---------|----------|
+/// | `SelectAlgorithm` | `EX.ALG` |
+/// | `SetParameter` | `EX.PARAM` |
+/// | `PlaySample` | `EX.SAMPLE` |
+/// | `SetZModeParameter` | `EX.Z` |
+/// | `LoadPreset` | `EX.PRESET` |
+/// | `SavePreset` | `EX.SAVE` |
+/// | `SelectSdFile` | `EX.SD` |
+/// | `NoteOn` | `EX.NOTE.ON` |
+/// | `NoteOff` | `EX.NOTE.OFF` |
+impl fmt::Display for Commands {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::SelectAlgorithm { index } => write!(f, "EX.ALG {index}"),
+            Self::SetParameter { param, value } => write!(f, "EX.PARAM {param} {value}"),
+            Self::PlaySample { slot } => write!(f, "EX.SAMPLE {slot}"),
+            Self::SetZModeParameter { param, value } => write!(f, "EX.Z {param} {value}"),
+            Self::LoadPreset { index } => write!(f, "EX.PRESET {index}"),
+            Self::SavePreset { index } => write!(f, "EX.SAVE {index}"),
+            Self::SelectSdFile { folder, file } => write!(f, "EX.SD {folder} {file}"),
+            Self::NoteOn { note, velocity } => write!(f, "EX.NOTE.ON {note} {velocity}"),
+            Self::NoteOff { note } => write!(f, "EX.NOTE.OFF {note}"),
+        }
+    }
+}
+
+/// Formats the command as its Teletype `EX.*` op, which matches its `Display` output.
+/// `SelectSdFile`, `NoteOn` and `NoteOff` are never wrapped, since they have no op.
+impl fmt::Display for TeletypeOp<Commands> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl Command for Commands {
+    const MAX_LENGTH: usize = 4; // SetParameter and SetZModeParameter are the longest.
+
+    fn to_bytes<'a>(&self, buffer: &'a mut [u8]) -> Result<&'a [u8], SerializationError> {
+        #[cfg(feature = "strict")]
+        self.validate()?;
+        #[cfg(not(feature = "strict"))]
+        debug_assert!(self.validate().is_ok(), "field out of its documented range");
+
+        let len = self.encode(buffer)?;
+        Ok(&buffer[..len])
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            Self::SelectAlgorithm { .. }
+            | Self::PlaySample { .. }
+            | Self::LoadPreset { .. }
+            | Self::SavePreset { .. }
+            | Self::NoteOff { .. } => 2,
+            Self::SelectSdFile { .. } | Self::NoteOn { .. } => 3,
+            Self::SetParameter { .. } | Self::SetZModeParameter { .. } => 4,
+        }
+    }
+
+    fn address(&self, index: u8) -> Option<u8> {
+        address(index)
+    }
+}
+
+impl Parse for Commands {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, usize), DeserializationError> {
+        let (&opcode, payload) = bytes
+            .split_first()
+            .ok_or(DeserializationError::UnexpectedEof)?;
+        let command = match (opcode, payload) {
+            (0x44, &[index, ..]) => Self::SelectAlgorithm { index },
+            (0x46, &[param, hi, lo, ..]) => Self::SetParameter {
+                param,
+                value: i16::from_be_bytes([hi, lo]),
+            },
+            (0x4D, &[slot, ..]) => Self::PlaySample { slot },
+            (0x4A, &[param, hi, lo, ..]) => Self::SetZModeParameter {
+                param,
+                value: i16::from_be_bytes([hi, lo]),
+            },
+            (0x40, &[index, ..]) => Self::LoadPreset { index },
+            (0x41, &[index, ..]) => Self::SavePreset { index },
+            (0x4B, &[folder, file, ..]) => Self::SelectSdFile { folder, file },
+            (0x55, &[note, velocity, ..]) => Self::NoteOn { note, velocity },
+            (0x56, &[note, ..]) => Self::NoteOff { note },
+            (0x40 | 0x41 | 0x44 | 0x46 | 0x4A | 0x4B | 0x4D | 0x55 | 0x56, _) => {
+                return Err(DeserializationError::UnexpectedEof);
+            }
+            (opcode, _) => return Err(DeserializationError::UnknownOpcode(opcode)),
+        };
+        Ok((command, command.len()))
+    }
+}
@@ -1,64 +1,446 @@
 //! Commands for the Orthogonal Devices ER-301.
+//!
+//! Every op in the ER-301's II table addresses a port: the Teletype `SC.*` ops cover
+//! trigger and CV outputs only, and the unit documents no port-less commands such as a
+//! reset or a version query. Use [`Raw`](crate::Raw) if a firmware adds one.
 
-use crate::{Command, SerializationError};
+use core::fmt;
 
-/// The default I2C address for the first ER-301. Addresses can go up to `0x33`.
-pub const ADDRESS: u8 = 0x31;
+use crate::units::{Millis, RawCv};
+use crate::{
+    Command, CommandCategory, DeserializationError, DeviceDescriptor, Parse, SerializationError,
+    TargetKey, TeletypeOp, check_range, parse_bool, put_i16_be, put_u16_be,
+};
+
+/// The default I2C address for the first ER-301, the same as [`Unit::A`].
+/// Addresses can go up to `0x33`.
+pub const ADDRESS: u8 = Unit::A.address();
+
+/// The I2C addresses of the up to three ER-301 units on one bus.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(u8)]
+pub enum Unit {
+    /// The first unit, at `0x31`.
+    A = 0x31,
+    /// The second unit, at `0x32`.
+    B = 0x32,
+    /// The third unit, at `0x33`.
+    C = 0x33,
+}
+
+impl Unit {
+    /// Returns the I2C address of the unit.
+    pub const fn address(self) -> u8 {
+        self as u8
+    }
+}
+
+/// Resolves the I2C address of the device with the given index.
+/// - `index`: 0-2, for the first to third unit.
+///
+/// Returns `None` if `index` is out of range.
+pub const fn address(index: u8) -> Option<u8> {
+    if index <= 2 {
+        Some(ADDRESS + index)
+    } else {
+        None
+    }
+}
+
+/// Metadata describing the ER-301.
+pub const DESCRIPTOR: DeviceDescriptor = DeviceDescriptor {
+    name: "ER-301",
+    default_address: ADDRESS,
+    address_range: ADDRESS..=0x33,
+    max_command_length: <Commands as Command>::MAX_LENGTH,
+    has_cv: true,
+    has_gates: true,
+    has_osc: false,
+    has_envelope: false,
+    cv_channels: 100,
+    gate_channels: 100,
+};
+
+/// Builds the commands that glide a CV output to `target` over `slew_ms` milliseconds.
+///
+/// The slew time only applies to CV changes sent after it, so the returned `SetCvSlew`
+/// must be sent before the `SetCv`.
+pub const fn glide(port: u8, target: i16, slew_ms: u16) -> [Commands; 2] {
+    [
+        Commands::SetCvSlew { port, ms: slew_ms },
+        Commands::SetCv {
+            port,
+            value: target,
+        },
+    ]
+}
 
 /// All supported II commands for the ER-301.
 #[allow(clippy::enum_variant_names)]
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Commands {
     /// `set gate`: Sets the state of a gate output.
     /// - `port`: 0-99
     /// - `state`: `true` for high (1), `false` for low (0).
     SetGate { port: u8, state: bool },
-    /// `set CV`: Sets the CV for a given output port.
+    /// `SC.TR.TOG`: Toggles the state of a trigger output.
+    /// - `port`: 0-99
+    SetTrToggle { port: u8 },
+    /// `SC.TR.PULSE`: Sends a pulse to a trigger output.
+    /// - `port`: 0-99
+    SetTrPulse { port: u8 },
+    /// `SC.TR.TIME`: Sets the pulse duration for a trigger output.
+    /// - `port`: 0-99
+    /// - `ms`: The pulse duration in milliseconds as an unsigned 16-bit integer.
+    SetTrTime { port: u8, ms: u16 },
+    /// `SC.TR.POL`: Sets the polarity of a trigger output.
+    /// - `port`: 0-99
+    /// - `polarity`: `true` for active high (1), `false` for inverted (0).
+    SetTrPolarity { port: u8, polarity: bool },
+    /// `set CV`: Sets the CV for a given output port. The output moves to the new value
+    /// over the slew time configured with `SetCvSlew` (opcode `0x11`).
     /// - `port`: 0-99
     /// - `value`: A signed 16-bit integer representing voltage.
     SetCv { port: u8, value: i16 },
+    /// `set CV immediate` (`SC.CV.SET`): Jumps a CV output to a value, ignoring and
+    /// cancelling any slew in progress (opcode `0x10`). Use this instead of `SetCv`
+    /// when a retrigger must land exactly on the new value.
+    /// - `port`: 0-99
+    /// - `value`: A signed 16-bit integer representing voltage.
+    SetCvImmediate { port: u8, value: i16 },
     /// `set CV slew`: Sets the slew time for a given CV output.
     /// - `port`: 0-99
     /// - `ms`: The slew time in milliseconds as an unsigned 16-bit integer.
     SetCvSlew { port: u8, ms: u16 },
 }
 
-impl Command for Commands {
-    const MAX_LENGTH: usize = 4; // SetCv and SetCvSlew are the longest.
+impl Commands {
+    /// Builds a `SetCv` command from a raw CV value.
+    pub const fn set_cv(port: u8, value: RawCv) -> Self {
+        Self::SetCv {
+            port,
+            value: value.0,
+        }
+    }
 
-    fn to_bytes<'a>(&self, buffer: &'a mut [u8]) -> Result<&'a [u8], SerializationError> {
+    /// Builds a `SetCvImmediate` command from a raw CV value.
+    pub const fn set_cv_immediate(port: u8, value: RawCv) -> Self {
+        Self::SetCvImmediate {
+            port,
+            value: value.0,
+        }
+    }
+
+    /// Builds a `SetTrTime` command from a pulse duration.
+    pub const fn set_tr_time(port: u8, duration: Millis) -> Self {
+        Self::SetTrTime {
+            port,
+            ms: duration.0,
+        }
+    }
+
+    /// Builds a `SetCvSlew` command from a slew time.
+    pub const fn set_cv_slew(port: u8, duration: Millis) -> Self {
+        Self::SetCvSlew {
+            port,
+            ms: duration.0,
+        }
+    }
+
+    /// Expands a bitmask into one `SetGate` command per port, where bit `n` sets port `n`.
+    /// The ER-301 has no native bitmask op, so this is done on the host and costs one II
+    /// write per port. Only ports 0-15 can be reached this way.
+    pub fn gates_from_mask(mask: u16) -> impl Iterator<Item = Self> {
+        (0..16).map(move |port| Self::SetGate {
+            port,
+            state: mask & (1 << port) != 0,
+        })
+    }
+
+    /// Returns the opcode this command writes as its first byte.
+    pub const fn opcode(&self) -> u8 {
+        match self {
+            Self::SetGate { .. } => 0x00,
+            Self::SetTrToggle { .. } => 0x13,
+            Self::SetTrPulse { .. } => 0x14,
+            Self::SetTrTime { .. } => 0x15,
+            Self::SetTrPolarity { .. } => 0x16,
+            Self::SetCv { .. } => 0x11,
+            Self::SetCvImmediate { .. } => 0x10,
+            Self::SetCvSlew { .. } => 0x12,
+        }
+    }
+
+    /// Returns the category of the command, e.g. for prioritizing II traffic.
+    pub const fn category(&self) -> CommandCategory {
+        match self {
+            Self::SetCv { .. } | Self::SetCvImmediate { .. } | Self::SetCvSlew { .. } => {
+                CommandCategory::Cv
+            }
+            Self::SetGate { .. }
+            | Self::SetTrToggle { .. }
+            | Self::SetTrPulse { .. }
+            | Self::SetTrTime { .. }
+            | Self::SetTrPolarity { .. } => CommandCategory::Gate,
+        }
+    }
+
+    /// Returns the equivalent Teletype `SC.*` op. Every command has one.
+    pub const fn teletype_op(&self) -> Option<TeletypeOp<Self>> {
+        Some(TeletypeOp(*self))
+    }
+
+    /// Returns the key identifying the destination the command writes to, see
+    /// [`TargetKey`].
+    ///
+    /// `SetCv` and `SetCvImmediate` to the same port share a key, since either overrides the
+    /// other. Returns `None` for toggles and pulses, which act on every send.
+    pub const fn target_key(&self) -> Option<TargetKey> {
+        match *self {
+            Self::SetTrToggle { .. } | Self::SetTrPulse { .. } => None,
+            // Shared keys use the opcode of the first variant in the arm.
+            Self::SetCv { port, .. } | Self::SetCvImmediate { port, .. } => {
+                Some(TargetKey::new(0x11, port))
+            }
+            Self::SetGate { port, .. }
+            | Self::SetTrTime { port, .. }
+            | Self::SetTrPolarity { port, .. }
+            | Self::SetCvSlew { port, .. } => Some(TargetKey::new(self.opcode(), port)),
+        }
+    }
+
+    /// Serializes the command into a fixed-size array, usable in `const` context.
+    ///
+    /// Returns the array and the number of valid bytes at its start. Unlike
+    /// [`Command::to_bytes`], this does not check field ranges.
+    pub const fn to_bytes_array(&self) -> ([u8; <Self as Command>::MAX_LENGTH], usize) {
+        let mut buffer = [0u8; <Self as Command>::MAX_LENGTH];
+        match self.encode(&mut buffer) {
+            Ok(len) => (buffer, len),
+            Err(_) => panic!("MAX_LENGTH is shorter than the command"),
+        }
+    }
+
+    /// Writes the command to the start of `buffer` and returns the number of bytes written.
+    const fn encode(&self, buffer: &mut [u8]) -> Result<usize, SerializationError> {
         match *self {
             Self::SetGate { port, state } => {
                 if buffer.len() < 3 {
-                    return Err(SerializationError::BufferTooSmall);
+                    return Err(SerializationError::buffer_too_small(3, buffer.len()));
                 }
-                buffer[0] = 0x00;
+                buffer[0] = self.opcode();
                 buffer[1] = port;
                 buffer[2] = state as u8;
-                Ok(&buffer[..3])
+                Ok(3)
+            }
+            Self::SetTrToggle { port } => {
+                if buffer.len() < 2 {
+                    return Err(SerializationError::buffer_too_small(2, buffer.len()));
+                }
+                buffer[0] = self.opcode();
+                buffer[1] = port;
+                Ok(2)
+            }
+            Self::SetTrPulse { port } => {
+                if buffer.len() < 2 {
+                    return Err(SerializationError::buffer_too_small(2, buffer.len()));
+                }
+                buffer[0] = self.opcode();
+                buffer[1] = port;
+                Ok(2)
+            }
+            Self::SetTrTime { port, ms } => {
+                if buffer.len() < 4 {
+                    return Err(SerializationError::buffer_too_small(4, buffer.len()));
+                }
+                buffer[0] = self.opcode();
+                buffer[1] = port;
+                put_u16_be(buffer, 2, ms);
+                Ok(4)
+            }
+            Self::SetTrPolarity { port, polarity } => {
+                if buffer.len() < 3 {
+                    return Err(SerializationError::buffer_too_small(3, buffer.len()));
+                }
+                buffer[0] = self.opcode();
+                buffer[1] = port;
+                buffer[2] = polarity as u8;
+                Ok(3)
             }
             Self::SetCv { port, value } => {
                 if buffer.len() < 4 {
-                    return Err(SerializationError::BufferTooSmall);
+                    return Err(SerializationError::buffer_too_small(4, buffer.len()));
                 }
-                let value_bytes = value.to_be_bytes(); // II protocol is big-endian
-                buffer[0] = 0x11;
+                buffer[0] = self.opcode();
                 buffer[1] = port;
-                buffer[2] = value_bytes[0];
-                buffer[3] = value_bytes[1];
-                Ok(&buffer[..4])
+                put_i16_be(buffer, 2, value);
+                Ok(4)
+            }
+            Self::SetCvImmediate { port, value } => {
+                if buffer.len() < 4 {
+                    return Err(SerializationError::buffer_too_small(4, buffer.len()));
+                }
+                buffer[0] = self.opcode();
+                buffer[1] = port;
+                put_i16_be(buffer, 2, value);
+                Ok(4)
             }
             Self::SetCvSlew { port, ms } => {
                 if buffer.len() < 4 {
-                    return Err(SerializationError::BufferTooSmall);
+                    return Err(SerializationError::buffer_too_small(4, buffer.len()));
                 }
-                let ms_bytes = ms.to_be_bytes();
-                buffer[0] = 0x12;
+                buffer[0] = self.opcode();
                 buffer[1] = port;
-                buffer[2] = ms_bytes[0];
-                buffer[3] = ms_bytes[1];
-                Ok(&buffer[..4])
+                put_u16_be(buffer, 2, ms);
+                Ok(4)
+            }
+        }
+    }
+
+    /// Checks the fields against the ranges documented on each variant.
+    fn validate(&self) -> Result<(), SerializationError> {
+        match *self {
+            Self::SetGate { port, .. }
+            | Self::SetTrToggle { port }
+            | Self::SetTrPulse { port }
+            | Self::SetTrTime { port, .. }
+            | Self::SetTrPolarity { port, .. }
+            | Self::SetCv { port, .. }
+            | Self::SetCvImmediate { port, .. }
+            | Self::SetCvSlew { port, .. } => check_range("port", port, 0..=99),
+        }
+    }
+}
+
+/// Formats the command as a Teletype-style mnemonic followed by its fields in declaration
+/// order, separated by spaces. Booleans are written as 0 or 1.
+///
+/// The mnemonics are stable, so tools may parse them from logs:
+///
+/// | Command | Mnemonic |
+/// |---------|----------|
+/// | `SetGate` | `ER301.TR` |
+/// | `SetTrToggle` | `ER301.TR.TOG` |
+/// | `SetTrPulse` | `ER301.TR.PULSE` |
+/// | `SetTrTime` | `ER301.TR.TIME` |
+/// | `SetTrPolarity` | `ER301.TR.POL` |
+/// | `SetCv` | `ER301.CV` |
+/// | `SetCvImmediate` | `ER301.CV.SET` |
+/// | `SetCvSlew` | `ER301.CV.SLEW` |
+impl fmt::Display for Commands {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::SetGate { port, state } => write!(f, "ER301.TR {port} {}", u8::from(state)),
+            Self::SetTrToggle { port } => write!(f, "ER301.TR.TOG {port}"),
+            Self::SetTrPulse { port } => write!(f, "ER301.TR.PULSE {port}"),
+            Self::SetTrTime { port, ms } => write!(f, "ER301.TR.TIME {port} {ms}"),
+            Self::SetTrPolarity { port, polarity } => {
+                write!(f, "ER301.TR.POL {port} {}", u8::from(polarity))
             }
+            Self::SetCv { port, value } => write!(f, "ER301.CV {port} {value}"),
+            Self::SetCvImmediate { port, value } => write!(f, "ER301.CV.SET {port} {value}"),
+            Self::SetCvSlew { port, ms } => write!(f, "ER301.CV.SLEW {port} {ms}"),
         }
     }
 }
+
+/// Formats the command as its Teletype `SC.*` op, which counts ports from 1.
+impl fmt::Display for TeletypeOp<Commands> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Commands::SetGate { port, state } => {
+                write!(f, "SC.TR {} {}", u16::from(port) + 1, u8::from(state))
+            }
+            Commands::SetTrToggle { port } => write!(f, "SC.TR.TOG {}", u16::from(port) + 1),
+            Commands::SetTrPulse { port } => write!(f, "SC.TR.PULSE {}", u16::from(port) + 1),
+            Commands::SetTrTime { port, ms } => {
+                write!(f, "SC.TR.TIME {} {ms}", u16::from(port) + 1)
+            }
+            Commands::SetTrPolarity { port, polarity } => write!(
+                f,
+                "SC.TR.POL {} {}",
+                u16::from(port) + 1,
+                u8::from(polarity)
+            ),
+            Commands::SetCv { port, value } => write!(f, "SC.CV {} {value}", u16::from(port) + 1),
+            Commands::SetCvImmediate { port, value } => {
+                write!(f, "SC.CV.SET {} {value}", u16::from(port) + 1)
+            }
+            Commands::SetCvSlew { port, ms } => {
+                write!(f, "SC.CV.SLEW {} {ms}", u16::from(port) + 1)
+            }
+        }
+    }
+}
+
+impl Command for Commands {
+    const MAX_LENGTH: usize = 4; // The CV commands and SetTrTime are the longest.
+
+    fn to_bytes<'a>(&self, buffer: &'a mut [u8]) -> Result<&'a [u8], SerializationError> {
+        #[cfg(feature = "strict")]
+        self.validate()?;
+        #[cfg(not(feature = "strict"))]
+        debug_assert!(self.validate().is_ok(), "field out of its documented range");
+
+        let len = self.encode(buffer)?;
+        Ok(&buffer[..len])
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            Self::SetTrToggle { .. } | Self::SetTrPulse { .. } => 2,
+            Self::SetGate { .. } | Self::SetTrPolarity { .. } => 3,
+            Self::SetTrTime { .. }
+            | Self::SetCv { .. }
+            | Self::SetCvImmediate { .. }
+            | Self::SetCvSlew { .. } => 4,
+        }
+    }
+
+    fn address(&self, index: u8) -> Option<u8> {
+        address(index)
+    }
+}
+
+impl Parse for Commands {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, usize), DeserializationError> {
+        let (&opcode, payload) = bytes
+            .split_first()
+            .ok_or(DeserializationError::UnexpectedEof)?;
+        let command = match (opcode, payload) {
+            (0x00, &[port, state, ..]) => Self::SetGate {
+                port,
+                state: parse_bool(state)?,
+            },
+            (0x13, &[port, ..]) => Self::SetTrToggle { port },
+            (0x14, &[port, ..]) => Self::SetTrPulse { port },
+            (0x15, &[port, hi, lo, ..]) => Self::SetTrTime {
+                port,
+                ms: u16::from_be_bytes([hi, lo]),
+            },
+            (0x16, &[port, polarity, ..]) => Self::SetTrPolarity {
+                port,
+                polarity: parse_bool(polarity)?,
+            },
+            (0x11, &[port, hi, lo, ..]) => Self::SetCv {
+                port,
+                value: i16::from_be_bytes([hi, lo]),
+            },
+            (0x10, &[port, hi, lo, ..]) => Self::SetCvImmediate {
+                port,
+                value: i16::from_be_bytes([hi, lo]),
+            },
+            (0x12, &[port, hi, lo, ..]) => Self::SetCvSlew {
+                port,
+                ms: u16::from_be_bytes([hi, lo]),
+            },
+            (0x00 | 0x10..=0x16, _) => return Err(DeserializationError::UnexpectedEof),
+            (opcode, _) => return Err(DeserializationError::UnknownOpcode(opcode)),
+        };
+        Ok((command, command.len()))
+    }
+}
@@ -0,0 +1,179 @@
+//! Commands for the 16n Faderbank.
+//!
+//! The 16n acts as an II leader and sends its fader positions to followers such as
+//! Ansible, the TXo or the ER-301. This module models those outgoing messages, so it
+//! exists mainly to simulate or test a faderbank's output and to replay captured fader
+//! streams; the messages are sent to the follower's address, not to `ADDRESS`.
+
+use core::fmt;
+
+use crate::{
+    Command, CommandCategory, DeserializationError, DeviceDescriptor, Parse, SerializationError,
+    TargetKey, TeletypeOp, put_u16_be,
+};
+
+/// The I2C address the 16n answers on when it is polled as a follower.
+pub const ADDRESS: u8 = 0x34;
+
+/// Resolves the I2C address of the device with the given index.
+/// The address is fixed, so only index 0 is valid and any other index returns `None`.
+pub const fn address(index: u8) -> Option<u8> {
+    match index {
+        0 => Some(ADDRESS),
+        _ => None,
+    }
+}
+
+/// Metadata describing the 16n Faderbank.
+pub const DESCRIPTOR: DeviceDescriptor = DeviceDescriptor {
+    name: "16n Faderbank",
+    default_address: ADDRESS,
+    address_range: ADDRESS..=ADDRESS,
+    max_command_length: <Commands as Command>::MAX_LENGTH,
+    has_cv: false,
+    has_gates: false,
+    has_osc: false,
+    has_envelope: false,
+    cv_channels: 0,
+    gate_channels: 0,
+};
+
+/// Computes the follower sub-address the 16n uses for a given fader.
+/// - `fader_index`: 0-15
+pub const fn device_port(fader_index: u8) -> u8 {
+    (fader_index / 4) << 1
+}
+
+/// All supported II messages sent by the 16n.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Commands {
+    /// Sends a fader position to a follower.
+    /// - `device_port`: The target sub-address, see [`device_port`].
+    /// - `value`: An unsigned 16-bit fader value.
+    SetCvFromFader { device_port: u8, value: u16 },
+}
+
+impl Commands {
+    /// Builds the message the 16n sends for the fader at `fader_index`.
+    /// - `fader_index`: 0-15
+    /// - `value`: An unsigned 16-bit fader value.
+    pub const fn from_fader(fader_index: u8, value: u16) -> Self {
+        Self::SetCvFromFader {
+            device_port: device_port(fader_index),
+            value,
+        }
+    }
+
+    /// Returns the opcode this command writes as its first byte.
+    pub const fn opcode(&self) -> u8 {
+        match self {
+            Self::SetCvFromFader { .. } => 0x06,
+        }
+    }
+
+    /// Returns the category of the command, e.g. for prioritizing II traffic.
+    pub const fn category(&self) -> CommandCategory {
+        match self {
+            Self::SetCvFromFader { .. } => CommandCategory::Cv,
+        }
+    }
+
+    /// Returns the equivalent Teletype op.
+    ///
+    /// Always returns `None`, because the 16n sends its commands as a leader, and Teletype only
+    /// reads the faders with `FB`, which has no write form.
+    pub const fn teletype_op(&self) -> Option<TeletypeOp<Self>> {
+        None
+    }
+
+    /// Returns the key identifying the destination the command writes to, see
+    /// [`TargetKey`].
+    ///
+    /// Every message has one, keyed by its `device_port`.
+    pub const fn target_key(&self) -> Option<TargetKey> {
+        match *self {
+            Self::SetCvFromFader { device_port, .. } => {
+                Some(TargetKey::new(self.opcode(), device_port))
+            }
+        }
+    }
+
+    /// Serializes the command into a fixed-size array, usable in `const` context.
+    ///
+    /// Returns the array and the number of valid bytes at its start. Unlike
+    /// [`Command::to_bytes`], this does not check field ranges.
+    pub const fn to_bytes_array(&self) -> ([u8; <Self as Command>::MAX_LENGTH], usize) {
+        let mut buffer = [0u8; <Self as Command>::MAX_LENGTH];
+        match self.encode(&mut buffer) {
+            Ok(len) => (buffer, len),
+            Err(_) => panic!("MAX_LENGTH is shorter than the command"),
+        }
+    }
+
+    /// Writes the command to the start of `buffer` and returns the number of bytes written.
+    const fn encode(&self, buffer: &mut [u8]) -> Result<usize, SerializationError> {
+        match *self {
+            Self::SetCvFromFader { device_port, value } => {
+                if buffer.len() < 4 {
+                    return Err(SerializationError::buffer_too_small(4, buffer.len()));
+                }
+                buffer[0] = self.opcode();
+                buffer[1] = device_port;
+                put_u16_be(buffer, 2, value);
+                Ok(4)
+            }
+        }
+    }
+}
+
+/// Formats the command as a Teletype-style mnemonic followed by its fields in declaration
+/// order, separated by spaces. Booleans are written as 0 or 1.
+///
+/// The mnemonics are stable, so tools may parse them from logs:
+///
+/// | Command | Mnemonic |
+/// |---------|----------|
+/// | `SetCvFromFader` | `16N.FADER` |
+impl fmt::Display for Commands {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::SetCvFromFader { device_port, value } => {
+                write!(f, "16N.FADER {device_port} {value}")
+            }
+        }
+    }
+}
+
+impl Command for Commands {
+    const MAX_LENGTH: usize = 4; // SetCvFromFader is the only command.
+
+    fn to_bytes<'a>(&self, buffer: &'a mut [u8]) -> Result<&'a [u8], SerializationError> {
+        let len = self.encode(buffer)?;
+        Ok(&buffer[..len])
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            Self::SetCvFromFader { .. } => 4,
+        }
+    }
+}
+
+impl Parse for Commands {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, usize), DeserializationError> {
+        let (&opcode, payload) = bytes
+            .split_first()
+            .ok_or(DeserializationError::UnexpectedEof)?;
+        let command = match (opcode, payload) {
+            (0x06, &[device_port, hi, lo, ..]) => Self::SetCvFromFader {
+                device_port,
+                value: u16::from_be_bytes([hi, lo]),
+            },
+            (0x06, _) => return Err(DeserializationError::UnexpectedEof),
+            (opcode, _) => return Err(DeserializationError::UnknownOpcode(opcode)),
+        };
+        Ok((command, command.len()))
+    }
+}
@@ -0,0 +1,287 @@
+//! Commands for the Expert Sleepers FH-2.
+//! The FH-2 follows the TXo opcode layout for its gate and CV outputs, but exposes far
+//! more than four of them, so `channel` is 0-63 rather than 0-3. Channels past 63 return
+//! `SerializationError::ValueOutOfRange`; without the `strict` feature they
+//! are passed through unchanged, not clamped.
+
+use core::fmt;
+
+use crate::{
+    Command, CommandCategory, DeserializationError, DeviceDescriptor, Parse, SerializationError,
+    TargetKey, TeletypeOp, check_range, parse_bool, put_i16_be, put_u16_be,
+};
+
+/// The default I2C address for the FH-2.
+pub const ADDRESS: u8 = 0x51;
+
+/// Resolves the I2C address of the device with the given index.
+/// The address is fixed, so only index 0 is valid and any other index returns `None`.
+pub const fn address(index: u8) -> Option<u8> {
+    match index {
+        0 => Some(ADDRESS),
+        _ => None,
+    }
+}
+
+/// Metadata describing the FH-2.
+pub const DESCRIPTOR: DeviceDescriptor = DeviceDescriptor {
+    name: "FH-2",
+    default_address: ADDRESS,
+    address_range: ADDRESS..=ADDRESS,
+    max_command_length: <Commands as Command>::MAX_LENGTH,
+    has_cv: true,
+    has_gates: true,
+    has_osc: false,
+    has_envelope: false,
+    cv_channels: 64,
+    gate_channels: 64,
+};
+
+/// All supported II commands for the FH-2.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Commands {
+    /// `set gate`: Sets the state of a gate output.
+    /// - `channel`: 0-63, counting the FH-2's own outputs first, then its expanders.
+    /// - `state`: `true` for high (1), `false` for low (0).
+    SetGate { channel: u8, state: bool },
+    /// `set CV`: Sets the CV for a given output.
+    /// - `channel`: 0-63, counting the FH-2's own outputs first, then its expanders.
+    /// - `value`: A signed 16-bit integer representing voltage.
+    SetCv { channel: u8, value: i16 },
+    /// `set clock division`: Sets the division applied to the FH-2's clock outputs.
+    /// - `value`: The divisor, 1-255. 0 is treated as 1 by the device.
+    SetClockDivision { value: u8 },
+    /// `set clock BPM`: Sets the tempo of the MIDI clock the FH-2 generates. This changes
+    /// the rate of the MIDI Timing Clock messages rather than sending a message itself.
+    /// - `bpm`: The tempo as an unsigned 16-bit integer.
+    SetClockBpm { bpm: u16 },
+    /// `start`: Starts the clock from the beginning, sending a MIDI Start message.
+    Start,
+    /// `stop`: Stops the clock, sending a MIDI Stop message.
+    Stop,
+    /// `continue`: Resumes the clock from where it stopped, sending a MIDI Continue message.
+    Continue,
+}
+
+impl Commands {
+    /// Returns the opcode this command writes as its first byte.
+    pub const fn opcode(&self) -> u8 {
+        match self {
+            Self::SetGate { .. } => 0x00,
+            Self::SetCv { .. } => 0x11,
+            Self::SetClockDivision { .. } => 0x20,
+            Self::SetClockBpm { .. } => 0x21,
+            Self::Start => 0x22,
+            Self::Stop => 0x23,
+            Self::Continue => 0x24,
+        }
+    }
+
+    /// Returns the category of the command, e.g. for prioritizing II traffic.
+    pub const fn category(&self) -> CommandCategory {
+        match self {
+            Self::SetCv { .. } => CommandCategory::Cv,
+            Self::SetGate { .. } => CommandCategory::Gate,
+            Self::SetClockDivision { .. }
+            | Self::SetClockBpm { .. }
+            | Self::Start
+            | Self::Stop
+            | Self::Continue => CommandCategory::Transport,
+        }
+    }
+
+    /// Returns the equivalent Teletype op.
+    ///
+    /// Always returns `None`, because Teletype has no ops that address the FH-2.
+    pub const fn teletype_op(&self) -> Option<TeletypeOp<Self>> {
+        None
+    }
+
+    /// Returns the key identifying the destination the command writes to, see
+    /// [`TargetKey`].
+    ///
+    /// Returns `None` for the transport commands, which act on every send.
+    pub const fn target_key(&self) -> Option<TargetKey> {
+        match *self {
+            Self::SetGate { channel, .. } | Self::SetCv { channel, .. } => {
+                Some(TargetKey::new(self.opcode(), channel))
+            }
+            Self::SetClockDivision { .. } | Self::SetClockBpm { .. } => {
+                Some(TargetKey::new(self.opcode(), 0))
+            }
+            Self::Start | Self::Stop | Self::Continue => None,
+        }
+    }
+
+    /// Serializes the command into a fixed-size array, usable in `const` context.
+    ///
+    /// Returns the array and the number of valid bytes at its start. Unlike
+    /// [`Command::to_bytes`], this does not check field ranges.
+    pub const fn to_bytes_array(&self) -> ([u8; <Self as Command>::MAX_LENGTH], usize) {
+        let mut buffer = [0u8; <Self as Command>::MAX_LENGTH];
+        match self.encode(&mut buffer) {
+            Ok(len) => (buffer, len),
+            Err(_) => panic!("MAX_LENGTH is shorter than the command"),
+        }
+    }
+
+    /// Writes the command to the start of `buffer` and returns the number of bytes written.
+    const fn encode(&self, buffer: &mut [u8]) -> Result<usize, SerializationError> {
+        match *self {
+            Self::SetGate { channel, state } => {
+                if buffer.len() < 3 {
+                    return Err(SerializationError::buffer_too_small(3, buffer.len()));
+                }
+                buffer[0] = self.opcode();
+                buffer[1] = channel;
+                buffer[2] = state as u8;
+                Ok(3)
+            }
+            Self::SetCv { channel, value } => {
+                if buffer.len() < 4 {
+                    return Err(SerializationError::buffer_too_small(4, buffer.len()));
+                }
+                buffer[0] = self.opcode();
+                buffer[1] = channel;
+                put_i16_be(buffer, 2, value);
+                Ok(4)
+            }
+            Self::SetClockDivision { value } => {
+                if buffer.len() < 2 {
+                    return Err(SerializationError::buffer_too_small(2, buffer.len()));
+                }
+                buffer[0] = self.opcode();
+                buffer[1] = value;
+                Ok(2)
+            }
+            Self::SetClockBpm { bpm } => {
+                if buffer.len() < 3 {
+                    return Err(SerializationError::buffer_too_small(3, buffer.len()));
+                }
+                buffer[0] = self.opcode();
+                put_u16_be(buffer, 1, bpm);
+                Ok(3)
+            }
+            Self::Start => {
+                if buffer.is_empty() {
+                    return Err(SerializationError::buffer_too_small(1, 0));
+                }
+                buffer[0] = self.opcode();
+                Ok(1)
+            }
+            Self::Stop => {
+                if buffer.is_empty() {
+                    return Err(SerializationError::buffer_too_small(1, 0));
+                }
+                buffer[0] = self.opcode();
+                Ok(1)
+            }
+            Self::Continue => {
+                if buffer.is_empty() {
+                    return Err(SerializationError::buffer_too_small(1, 0));
+                }
+                buffer[0] = self.opcode();
+                Ok(1)
+            }
+        }
+    }
+
+    /// Checks the fields against the ranges documented on each variant.
+    fn validate(&self) -> Result<(), SerializationError> {
+        match *self {
+            Self::SetGate { channel, .. } | Self::SetCv { channel, .. } => {
+                check_range("channel", channel, 0..=63)
+            }
+            Self::SetClockDivision { .. }
+            | Self::SetClockBpm { .. }
+            | Self::Start
+            | Self::Stop
+            | Self::Continue => Ok(()),
+        }
+    }
+}
+
+/// Formats the command as a Teletype-style mnemonic followed by its fields in declaration
+/// order, separated by spaces. Booleans are written as 0 or 1.
+///
+/// The mnemonics are stable, so tools may parse them from logs:
+///
+/// | Command | Mnemonic |
+/// |---------|----------|
+/// | `SetGate` | `FH2.TR` |
+/// | `SetCv` | `FH2.CV` |
+/// | `SetClockDivision` | `FH2.CLK.DIV` |
+/// | `SetClockBpm` | `FH2.CLK.BPM` |
+/// | `Start` | `FH2.START` |
+/// | `Stop` | `FH2.STOP` |
+/// | `Continue` | `FH2.CONT` |
+impl fmt::Display for Commands {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::SetGate { channel, state } => write!(f, "FH2.TR {channel} {}", u8::from(state)),
+            Self::SetCv { channel, value } => write!(f, "FH2.CV {channel} {value}"),
+            Self::SetClockDivision { value } => write!(f, "FH2.CLK.DIV {value}"),
+            Self::SetClockBpm { bpm } => write!(f, "FH2.CLK.BPM {bpm}"),
+            Self::Start => f.write_str("FH2.START"),
+            Self::Stop => f.write_str("FH2.STOP"),
+            Self::Continue => f.write_str("FH2.CONT"),
+        }
+    }
+}
+
+impl Command for Commands {
+    const MAX_LENGTH: usize = 4; // SetCv is the longest command.
+
+    fn to_bytes<'a>(&self, buffer: &'a mut [u8]) -> Result<&'a [u8], SerializationError> {
+        #[cfg(feature = "strict")]
+        self.validate()?;
+        #[cfg(not(feature = "strict"))]
+        debug_assert!(self.validate().is_ok(), "field out of its documented range");
+
+        let len = self.encode(buffer)?;
+        Ok(&buffer[..len])
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            Self::Start | Self::Stop | Self::Continue => 1,
+            Self::SetClockDivision { .. } => 2,
+            Self::SetGate { .. } | Self::SetClockBpm { .. } => 3,
+            Self::SetCv { .. } => 4,
+        }
+    }
+
+    fn address(&self, _index: u8) -> Option<u8> {
+        Some(ADDRESS)
+    }
+}
+
+impl Parse for Commands {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, usize), DeserializationError> {
+        let (&opcode, payload) = bytes
+            .split_first()
+            .ok_or(DeserializationError::UnexpectedEof)?;
+        let command = match (opcode, payload) {
+            (0x00, &[channel, state, ..]) => Self::SetGate {
+                channel,
+                state: parse_bool(state)?,
+            },
+            (0x11, &[channel, hi, lo, ..]) => Self::SetCv {
+                channel,
+                value: i16::from_be_bytes([hi, lo]),
+            },
+            (0x20, &[value, ..]) => Self::SetClockDivision { value },
+            (0x21, &[hi, lo, ..]) => Self::SetClockBpm {
+                bpm: u16::from_be_bytes([hi, lo]),
+            },
+            (0x22, _) => Self::Start,
+            (0x23, _) => Self::Stop,
+            (0x24, _) => Self::Continue,
+            (0x00 | 0x11 | 0x20 | 0x21, _) => return Err(DeserializationError::UnexpectedEof),
+            (opcode, _) => return Err(DeserializationError::UnknownOpcode(opcode)),
+        };
+        Ok((command, command.len()))
+    }
+}
@@ -1,37 +1,280 @@
 //! Commands for the Mannequins / Whimsical Raps Just Friends.
 
-use crate::{Command, SerializationError};
+use core::fmt;
+
+use crate::{
+    BuildError, Command, CommandCategory, Cursor, DeserializationError, DeviceDescriptor, Parse,
+    SerializationError, TargetKey, TeletypeOp, check_build_range, check_range, parse_bool,
+    put_i16_be,
+};
 
 /// The fixed I2C address for Just Friends.
 pub const ADDRESS: u8 = 0x70;
 
+/// Resolves the I2C address of the device with the given index.
+/// The address is fixed, so only index 0 is valid and any other index returns `None`.
+pub const fn address(index: u8) -> Option<u8> {
+    match index {
+        0 => Some(ADDRESS),
+        _ => None,
+    }
+}
+
+/// Metadata describing the Just Friends.
+pub const DESCRIPTOR: DeviceDescriptor = DeviceDescriptor {
+    name: "Just Friends",
+    default_address: ADDRESS,
+    address_range: ADDRESS..=ADDRESS,
+    max_command_length: <Commands as Command>::MAX_LENGTH,
+    has_cv: false,
+    has_gates: true,
+    has_osc: true,
+    has_envelope: false,
+    cv_channels: 0,
+    gate_channels: 6,
+};
+
+/// The `output` and `channel` value that addresses all six outputs at once.
+pub const ALL_OUTPUTS: u8 = 0;
+
+/// The target of a command that addresses one or all outputs, e.g. to play a note on
+/// every voice at once.
+///
+/// Converts into the raw `output` field with [`TryFrom`], which checks that `One` is 1-6.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Output {
+    /// All outputs, serialized as [`ALL_OUTPUTS`].
+    All,
+    /// A single output, 1-6.
+    One(u8),
+}
+
+impl TryFrom<Output> for u8 {
+    type Error = BuildError;
+
+    fn try_from(output: Output) -> Result<Self, Self::Error> {
+        match output {
+            Output::All => Ok(ALL_OUTPUTS),
+            Output::One(n) => {
+                check_build_range("output", n, 1..=6)?;
+                Ok(n)
+            }
+        }
+    }
+}
+
+/// Serializes one `PlayNote` per `(output, pitch, volume)` into `buffer`, back to back, so
+/// a chord can be sent in a single transfer.
+///
+/// Returns `SerializationError::ValueOutOfRange` if an output is not 1-6, since a chord
+/// targets individual voices, and `SerializationError::BufferTooSmall` with the length of
+/// the whole chord if it does not fit.
+pub fn chord<'a>(
+    notes: &[(u8, i16, i16)],
+    buffer: &'a mut [u8],
+) -> Result<&'a [u8], SerializationError> {
+    let got = buffer.len();
+    let mut cursor = Cursor::new(buffer);
+    for &(output, pitch, volume) in notes {
+        check_range("output", output, 1..=6)?;
+        let command = Commands::PlayNote {
+            output,
+            pitch,
+            volume,
+        };
+        match cursor.push_command(&command) {
+            Ok(()) => {}
+            Err(SerializationError::BufferTooSmall { .. }) => {
+                let needed = notes.len() * command.len();
+                return Err(SerializationError::buffer_too_small(needed, got));
+            }
+            Err(error) => return Err(error),
+        }
+    }
+    Ok(cursor.finish())
+}
+
 /// All supported II commands for Just Friends.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Commands {
     /// `set gate`: Sets the state of a gate output.
-    /// - `output`: 1-6, or 0 for all.
+    /// - `output`: 1-6, or [`ALL_OUTPUTS`] for all.
     /// - `state`: `true` for high (1), `false` for low (0).
     SetGate { output: u8, state: bool },
     /// `play note`: Triggers a note on one or all outputs.
-    /// - `output`: 1-6, or 0 for all.
+    /// - `output`: 1-6, or [`ALL_OUTPUTS`] for all.
     /// - `pitch`: Signed 16-bit note pitch value.
     /// - `volume`: Signed 16-bit velocity/volume value.
     PlayNote { output: u8, pitch: i16, volume: i16 },
+    /// `jf.vox`: Sets the pitch and level of a voice without retriggering it.
+    /// - `channel`: 1-6, or [`ALL_OUTPUTS`] for all.
+    /// - `pitch`: Signed 16-bit note pitch value.
+    /// - `level`: Signed 16-bit level value.
+    SetVoice { channel: u8, pitch: i16, level: i16 },
+    /// `jf.intone`: Sets the INTONE spread of a voice.
+    /// - `channel`: 1-6, or [`ALL_OUTPUTS`] for all.
+    /// - `ratio`: Signed 16-bit intone value.
+    SetIntone { channel: u8, ratio: i16 },
+    /// `jf.mode`: Switches between the standard and the alternate (Synthesis/Geode) mode.
+    /// - `on`: `true` for the alternate mode (1), `false` for the standard mode (0).
+    SetMode { on: bool },
+    /// `jf.run`: Sets the value of the RUN input.
+    /// - `value`: A signed 16-bit integer scaled like a CV, representing voltage.
+    SetRun { value: i16 },
+    /// `jf.run_mode`: Selects how the RUN input is interpreted.
+    /// - `mode`: 0 for free-running behavior, ignoring RUN; 1 for RUN mode, where RUN
+    ///   shapes the behavior of the active mode, e.g. clocking in Geode mode.
+    SetRunMode { mode: u8 },
+    /// `jf.shift`: Transposes all outputs.
+    /// - `value`: A signed 16-bit pitch offset.
+    SetTranspose { value: i16 },
+    /// `jf.ramp`: Sets the RAMP of all envelopes in Geode mode.
+    /// - `value`: Signed 16-bit value, 0 being the neutral (noon) position.
+    SetRamp { value: i16 },
+    /// `jf.curve`: Sets the CURVE of all envelopes in Geode mode.
+    /// - `value`: Signed 16-bit value, 0 being the neutral (noon) position.
+    SetCurve { value: i16 },
+    /// `jf.fm`: Sets the FM depth applied to all envelopes in Geode mode.
+    /// - `value`: Signed 16-bit value, 0 being the neutral (noon) position.
+    SetFm { value: i16 },
+    /// `jf.time`: Sets the duration of the events a channel plays in Geode mode.
+    /// - `channel`: 1-6, or [`ALL_OUTPUTS`] (0) for all.
+    /// - `value`: Signed 16-bit duration, scaled like the TIME knob.
+    SetTime { channel: u8, value: i16 },
+    /// `jf.quantize`: Enables pitch quantization for `PlayNote` and `SetVoice`.
+    /// - `on`: `true` to quantize (1), `false` to play pitches as-is (0).
+    SetQuantize { on: bool },
+    /// `jf.tune`: Sets the tuning offset of a voice.
+    /// - `channel`: 1-6, or [`ALL_OUTPUTS`] for all, like `output` in `SetGate`.
+    /// - `value`: A signed 16-bit pitch offset.
+    Tune { channel: u8, value: i16 },
+    /// `jf.god`: Switches the tuning reference of all voices.
+    /// - `on`: `true` for god mode, tuned to A = 432 Hz (1), `false` for A = 440 Hz (0).
+    SetGodMode { on: bool },
 }
 
-impl Command for Commands {
-    const MAX_LENGTH: usize = 6; // PlayNote is the longest command.
+impl Commands {
+    /// Builds a `SetGate` command, checking that a single `output` is 1-6.
+    pub fn set_gate(output: Output, state: bool) -> Result<Self, BuildError> {
+        Ok(Self::SetGate {
+            output: output.try_into()?,
+            state,
+        })
+    }
 
-    fn to_bytes<'a>(&self, buffer: &'a mut [u8]) -> Result<&'a [u8], SerializationError> {
+    /// Builds a `PlayNote` command, checking that a single `output` is 1-6.
+    pub fn play_note(output: Output, pitch: i16, volume: i16) -> Result<Self, BuildError> {
+        Ok(Self::PlayNote {
+            output: output.try_into()?,
+            pitch,
+            volume,
+        })
+    }
+
+    /// Returns the opcode this command writes as its first byte.
+    pub const fn opcode(&self) -> u8 {
+        match self {
+            Self::SetGate { .. } => 0x01,
+            Self::PlayNote { .. } => 0x08,
+            Self::SetVoice { .. } => 0x09,
+            Self::SetIntone { .. } => 0x15,
+            Self::SetMode { .. } => 0x06,
+            Self::SetRun { .. } => 0x03,
+            Self::SetRunMode { .. } => 0x02,
+            Self::SetTranspose { .. } => 0x04,
+            Self::SetRamp { .. } => 0x11,
+            Self::SetCurve { .. } => 0x12,
+            Self::SetFm { .. } => 0x13,
+            Self::SetTime { .. } => 0x14,
+            Self::SetQuantize { .. } => 0x0C,
+            Self::Tune { .. } => 0x0B,
+            Self::SetGodMode { .. } => 0x0A,
+        }
+    }
+
+    /// Returns the category of the command, e.g. for prioritizing II traffic.
+    pub const fn category(&self) -> CommandCategory {
+        match self {
+            Self::SetVoice { .. } | Self::SetRun { .. } => CommandCategory::Cv,
+            Self::SetGate { .. } | Self::PlayNote { .. } => CommandCategory::Gate,
+            Self::SetIntone { .. }
+            | Self::SetRamp { .. }
+            | Self::SetCurve { .. }
+            | Self::SetFm { .. }
+            | Self::SetTime { .. } => CommandCategory::Osc,
+            Self::SetMode { .. }
+            | Self::SetRunMode { .. }
+            | Self::SetTranspose { .. }
+            | Self::SetQuantize { .. }
+            | Self::Tune { .. }
+            | Self::SetGodMode { .. } => CommandCategory::Mode,
+        }
+    }
+
+    /// Returns the equivalent Teletype `JF.*` op.
+    ///
+    /// Returns `None` for a `PlayNote` to a single output, since Teletype's `JF.NOTE`
+    /// always picks the voice itself.
+    pub const fn teletype_op(&self) -> Option<TeletypeOp<Self>> {
+        match self {
+            Self::PlayNote { output, .. } if *output != ALL_OUTPUTS => None,
+            _ => Some(TeletypeOp(*self)),
+        }
+    }
+
+    /// Returns the key identifying the destination the command writes to, see
+    /// [`TargetKey`].
+    ///
+    /// Keys compare the raw `output` or `channel`, so a command to [`ALL_OUTPUTS`] does not
+    /// share a key with one to a single output. Returns `None` for `PlayNote`, which
+    /// plays on every send.
+    pub const fn target_key(&self) -> Option<TargetKey> {
+        match *self {
+            Self::PlayNote { .. } => None,
+            Self::SetGate { output, .. } => Some(TargetKey::new(self.opcode(), output)),
+            Self::SetVoice { channel, .. }
+            | Self::SetIntone { channel, .. }
+            | Self::SetTime { channel, .. }
+            | Self::Tune { channel, .. } => Some(TargetKey::new(self.opcode(), channel)),
+            Self::SetMode { .. }
+            | Self::SetRun { .. }
+            | Self::SetRunMode { .. }
+            | Self::SetTranspose { .. }
+            | Self::SetRamp { .. }
+            | Self::SetCurve { .. }
+            | Self::SetFm { .. }
+            | Self::SetQuantize { .. }
+            | Self::SetGodMode { .. } => Some(TargetKey::new(self.opcode(), 0)),
+        }
+    }
+
+    /// Serializes the command into a fixed-size array, usable in `const` context.
+    ///
+    /// Returns the array and the number of valid bytes at its start. Unlike
+    /// [`Command::to_bytes`], this does not check field ranges.
+    pub const fn to_bytes_array(&self) -> ([u8; <Self as Command>::MAX_LENGTH], usize) {
+        let mut buffer = [0u8; <Self as Command>::MAX_LENGTH];
+        match self.encode(&mut buffer) {
+            Ok(len) => (buffer, len),
+            Err(_) => panic!("MAX_LENGTH is shorter than the command"),
+        }
+    }
+
+    /// Writes the command to the start of `buffer` and returns the number of bytes written.
+    const fn encode(&self, buffer: &mut [u8]) -> Result<usize, SerializationError> {
         match *self {
             Self::SetGate { output, state } => {
                 if buffer.len() < 3 {
-                    return Err(SerializationError::BufferTooSmall);
+                    return Err(SerializationError::buffer_too_small(3, buffer.len()));
                 }
-                buffer[0] = 0x01;
+                buffer[0] = self.opcode();
                 buffer[1] = output;
                 buffer[2] = state as u8;
-                Ok(&buffer[..3])
+                Ok(3)
             }
             Self::PlayNote {
                 output,
@@ -39,18 +282,313 @@ impl Command for Commands {
                 volume,
             } => {
                 if buffer.len() < 6 {
-                    return Err(SerializationError::BufferTooSmall);
+                    return Err(SerializationError::buffer_too_small(6, buffer.len()));
                 }
-                let pitch_bytes = pitch.to_be_bytes();
-                let volume_bytes = volume.to_be_bytes();
-                buffer[0] = 0x08;
+                buffer[0] = self.opcode();
                 buffer[1] = output;
-                buffer[2] = pitch_bytes[0];
-                buffer[3] = pitch_bytes[1];
-                buffer[4] = volume_bytes[0];
-                buffer[5] = volume_bytes[1];
-                Ok(&buffer[..6])
+                put_i16_be(buffer, 2, pitch);
+                put_i16_be(buffer, 4, volume);
+                Ok(6)
+            }
+            Self::SetVoice {
+                channel,
+                pitch,
+                level,
+            } => {
+                if buffer.len() < 6 {
+                    return Err(SerializationError::buffer_too_small(6, buffer.len()));
+                }
+                buffer[0] = self.opcode();
+                buffer[1] = channel;
+                put_i16_be(buffer, 2, pitch);
+                put_i16_be(buffer, 4, level);
+                Ok(6)
+            }
+            Self::SetIntone { channel, ratio } => {
+                if buffer.len() < 4 {
+                    return Err(SerializationError::buffer_too_small(4, buffer.len()));
+                }
+                buffer[0] = self.opcode();
+                buffer[1] = channel;
+                put_i16_be(buffer, 2, ratio);
+                Ok(4)
+            }
+            Self::SetMode { on } => {
+                if buffer.len() < 2 {
+                    return Err(SerializationError::buffer_too_small(2, buffer.len()));
+                }
+                buffer[0] = self.opcode();
+                buffer[1] = on as u8;
+                Ok(2)
+            }
+            Self::SetRun { value } => {
+                if buffer.len() < 3 {
+                    return Err(SerializationError::buffer_too_small(3, buffer.len()));
+                }
+                buffer[0] = self.opcode();
+                put_i16_be(buffer, 1, value);
+                Ok(3)
+            }
+            Self::SetRunMode { mode } => {
+                if buffer.len() < 2 {
+                    return Err(SerializationError::buffer_too_small(2, buffer.len()));
+                }
+                buffer[0] = self.opcode();
+                buffer[1] = mode;
+                Ok(2)
+            }
+            Self::SetTranspose { value } => {
+                if buffer.len() < 3 {
+                    return Err(SerializationError::buffer_too_small(3, buffer.len()));
+                }
+                buffer[0] = self.opcode();
+                put_i16_be(buffer, 1, value);
+                Ok(3)
+            }
+            Self::SetRamp { value } => {
+                if buffer.len() < 3 {
+                    return Err(SerializationError::buffer_too_small(3, buffer.len()));
+                }
+                buffer[0] = self.opcode();
+                put_i16_be(buffer, 1, value);
+                Ok(3)
+            }
+            Self::SetCurve { value } => {
+                if buffer.len() < 3 {
+                    return Err(SerializationError::buffer_too_small(3, buffer.len()));
+                }
+                buffer[0] = self.opcode();
+                put_i16_be(buffer, 1, value);
+                Ok(3)
+            }
+            Self::SetFm { value } => {
+                if buffer.len() < 3 {
+                    return Err(SerializationError::buffer_too_small(3, buffer.len()));
+                }
+                buffer[0] = self.opcode();
+                put_i16_be(buffer, 1, value);
+                Ok(3)
+            }
+            Self::SetTime { channel, value } => {
+                if buffer.len() < 4 {
+                    return Err(SerializationError::buffer_too_small(4, buffer.len()));
+                }
+                buffer[0] = self.opcode();
+                buffer[1] = channel;
+                put_i16_be(buffer, 2, value);
+                Ok(4)
+            }
+            Self::SetQuantize { on } => {
+                if buffer.len() < 2 {
+                    return Err(SerializationError::buffer_too_small(2, buffer.len()));
+                }
+                buffer[0] = self.opcode();
+                buffer[1] = on as u8;
+                Ok(2)
+            }
+            Self::Tune { channel, value } => {
+                if buffer.len() < 4 {
+                    return Err(SerializationError::buffer_too_small(4, buffer.len()));
+                }
+                buffer[0] = self.opcode();
+                buffer[1] = channel;
+                put_i16_be(buffer, 2, value);
+                Ok(4)
+            }
+            Self::SetGodMode { on } => {
+                if buffer.len() < 2 {
+                    return Err(SerializationError::buffer_too_small(2, buffer.len()));
+                }
+                buffer[0] = self.opcode();
+                buffer[1] = on as u8;
+                Ok(2)
+            }
+        }
+    }
+
+    /// Checks the fields against the ranges documented on each variant.
+    fn validate(&self) -> Result<(), SerializationError> {
+        match *self {
+            // 0 is `ALL_OUTPUTS`; anything above 6 names no output and is ignored by JF.
+            Self::SetGate { output, .. } | Self::PlayNote { output, .. } => {
+                check_range("output", output, ALL_OUTPUTS..=6)
             }
+            Self::SetVoice { channel, .. }
+            | Self::SetIntone { channel, .. }
+            | Self::Tune { channel, .. }
+            | Self::SetTime { channel, .. } => check_range("channel", channel, 0..=6),
+            Self::SetRunMode { mode } => check_range("mode", mode, 0..=1),
+            Self::SetMode { .. }
+            | Self::SetQuantize { .. }
+            | Self::SetGodMode { .. }
+            | Self::SetRun { .. }
+            | Self::SetTranspose { .. }
+            | Self::SetRamp { .. }
+            | Self::SetCurve { .. }
+            | Self::SetFm { .. } => Ok(()),
+        }
+    }
+}
+
+/// Formats the command as a Teletype-style mnemonic followed by its fields in declaration
+/// order, separated by spaces. Booleans are written as 0 or 1.
+///
+/// The mnemonics are stable, so tools may parse them from logs:
+///
+/// | Command | Mnemonic |
+/// |---------|----------|
+/// | `SetGate` | `JF.TR` |
+/// | `PlayNote` | `JF.NOTE` |
+/// | `SetVoice` | `JF.VOX` |
+/// | `SetIntone` | `JF.INTONE` |
+/// | `SetMode` | `JF.MODE` |
+/// | `SetRun` | `JF.RUN` |
+/// | `SetRunMode` | `JF.RMODE` |
+/// | `SetTranspose` | `JF.SHIFT` |
+/// | `SetRamp` | `JF.RAMP` |
+/// | `SetCurve` | `JF.CURVE` |
+/// | `SetFm` | `JF.FM` |
+/// | `SetTime` | `JF.TIME` |
+/// | `SetQuantize` | `JF.QT` |
+/// | `Tune` | `JF.TUNE` |
+/// | `SetGodMode` | `JF.GOD` |
+impl fmt::Display for Commands {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::SetGate { output, state } => write!(f, "JF.TR {output} {}", u8::from(state)),
+            Self::PlayNote {
+                output,
+                pitch,
+                volume,
+            } => write!(f, "JF.NOTE {output} {pitch} {volume}"),
+            Self::SetVoice {
+                channel,
+                pitch,
+                level,
+            } => write!(f, "JF.VOX {channel} {pitch} {level}"),
+            Self::SetIntone { channel, ratio } => write!(f, "JF.INTONE {channel} {ratio}"),
+            Self::SetMode { on } => write!(f, "JF.MODE {}", u8::from(on)),
+            Self::SetRun { value } => write!(f, "JF.RUN {value}"),
+            Self::SetRunMode { mode } => write!(f, "JF.RMODE {mode}"),
+            Self::SetTranspose { value } => write!(f, "JF.SHIFT {value}"),
+            Self::SetRamp { value } => write!(f, "JF.RAMP {value}"),
+            Self::SetCurve { value } => write!(f, "JF.CURVE {value}"),
+            Self::SetFm { value } => write!(f, "JF.FM {value}"),
+            Self::SetTime { channel, value } => write!(f, "JF.TIME {channel} {value}"),
+            Self::SetQuantize { on } => write!(f, "JF.QT {}", u8::from(on)),
+            Self::Tune { channel, value } => write!(f, "JF.TUNE {channel} {value}"),
+            Self::SetGodMode { on } => write!(f, "JF.GOD {}", u8::from(on)),
+        }
+    }
+}
+
+/// Formats the command as its Teletype `JF.*` op. Outputs are already counted from 1.
+impl fmt::Display for TeletypeOp<Commands> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Commands::PlayNote { pitch, volume, .. } => write!(f, "JF.NOTE {pitch} {volume}"),
+            command => fmt::Display::fmt(&command, f),
+        }
+    }
+}
+
+impl Command for Commands {
+    const MAX_LENGTH: usize = 6; // PlayNote and SetVoice are the longest.
+
+    fn to_bytes<'a>(&self, buffer: &'a mut [u8]) -> Result<&'a [u8], SerializationError> {
+        #[cfg(feature = "strict")]
+        self.validate()?;
+        #[cfg(not(feature = "strict"))]
+        debug_assert!(self.validate().is_ok(), "field out of its documented range");
+
+        let len = self.encode(buffer)?;
+        Ok(&buffer[..len])
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            Self::SetMode { .. }
+            | Self::SetQuantize { .. }
+            | Self::SetRunMode { .. }
+            | Self::SetGodMode { .. } => 2,
+            Self::SetGate { .. }
+            | Self::SetRun { .. }
+            | Self::SetTranspose { .. }
+            | Self::SetRamp { .. }
+            | Self::SetCurve { .. }
+            | Self::SetFm { .. } => 3,
+            Self::SetIntone { .. } | Self::Tune { .. } | Self::SetTime { .. } => 4,
+            Self::PlayNote { .. } | Self::SetVoice { .. } => 6,
         }
     }
+
+    fn address(&self, _index: u8) -> Option<u8> {
+        Some(ADDRESS)
+    }
+}
+
+impl Parse for Commands {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, usize), DeserializationError> {
+        let (&opcode, payload) = bytes
+            .split_first()
+            .ok_or(DeserializationError::UnexpectedEof)?;
+        let command = match (opcode, payload) {
+            (0x01, &[output, state, ..]) => Self::SetGate {
+                output,
+                state: parse_bool(state)?,
+            },
+            (0x08, &[output, pitch_hi, pitch_lo, volume_hi, volume_lo, ..]) => Self::PlayNote {
+                output,
+                pitch: i16::from_be_bytes([pitch_hi, pitch_lo]),
+                volume: i16::from_be_bytes([volume_hi, volume_lo]),
+            },
+            (0x09, &[channel, pitch_hi, pitch_lo, level_hi, level_lo, ..]) => Self::SetVoice {
+                channel,
+                pitch: i16::from_be_bytes([pitch_hi, pitch_lo]),
+                level: i16::from_be_bytes([level_hi, level_lo]),
+            },
+            (0x15, &[channel, hi, lo, ..]) => Self::SetIntone {
+                channel,
+                ratio: i16::from_be_bytes([hi, lo]),
+            },
+            (0x06, &[on, ..]) => Self::SetMode {
+                on: parse_bool(on)?,
+            },
+            (0x03, &[hi, lo, ..]) => Self::SetRun {
+                value: i16::from_be_bytes([hi, lo]),
+            },
+            (0x02, &[mode, ..]) => Self::SetRunMode { mode },
+            (0x04, &[hi, lo, ..]) => Self::SetTranspose {
+                value: i16::from_be_bytes([hi, lo]),
+            },
+            (0x11, &[hi, lo, ..]) => Self::SetRamp {
+                value: i16::from_be_bytes([hi, lo]),
+            },
+            (0x12, &[hi, lo, ..]) => Self::SetCurve {
+                value: i16::from_be_bytes([hi, lo]),
+            },
+            (0x13, &[hi, lo, ..]) => Self::SetFm {
+                value: i16::from_be_bytes([hi, lo]),
+            },
+            (0x14, &[channel, hi, lo, ..]) => Self::SetTime {
+                channel,
+                value: i16::from_be_bytes([hi, lo]),
+            },
+            (0x0C, &[on, ..]) => Self::SetQuantize {
+                on: parse_bool(on)?,
+            },
+            (0x0B, &[channel, hi, lo, ..]) => Self::Tune {
+                channel,
+                value: i16::from_be_bytes([hi, lo]),
+            },
+            (0x0A, &[on, ..]) => Self::SetGodMode {
+                on: parse_bool(on)?,
+            },
+            (0x01..=0x04 | 0x06 | 0x08..=0x0C | 0x11..=0x15, _) => {
+                return Err(DeserializationError::UnexpectedEof);
+            }
+            (opcode, _) => return Err(DeserializationError::UnknownOpcode(opcode)),
+        };
+        Ok((command, command.len()))
+    }
 }
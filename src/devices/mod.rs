@@ -1,16 +1,19 @@
 // Maybe we want to implement more in the future
-// Crow = 0x01,
 // Ww = 0x10,
 // Mp = 0x30,
-// Faderbank = 0x34,
 // Matrixarchate = 0x38,
 // Tetrapad = 0x3b,
 // Orca = 0x40,
-// DistingEx = 0x41,
 // Es = 0x50,
-// TelexI = 0x68,
 
 pub mod ansible;
+pub mod crow;
+pub mod disting_ex;
 pub mod er301;
+pub mod faderbank;
+pub mod fh2;
 pub mod just_friends;
+pub mod teletype;
+pub mod telexi;
 pub mod telexo;
+pub mod w;
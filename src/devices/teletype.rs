@@ -0,0 +1,212 @@
+//! Commands for a Monome Teletype acting as an II follower.
+//! The final address is the `BASE_ADDRESS` plus the device index (0-3), which
+//! [`address`] calculates.
+//!
+//! A Teletype is normally the II leader and ignores writes to its address. All of these
+//! commands require the target Teletype to have follower mode enabled, which lets one
+//! Teletype (or any other host) run scripts and edit patterns on another. The first
+//! follower answers on `0x74`, since `0x70` and `0x71` belong to Just Friends and W/.
+
+use core::fmt;
+
+use crate::{
+    Command, CommandCategory, DeserializationError, DeviceDescriptor, Parse, SerializationError,
+    TargetKey, TeletypeOp, check_range, put_i16_be,
+};
+
+/// The base I2C address for Teletype followers.
+pub const BASE_ADDRESS: u8 = 0x74;
+
+/// Resolves the I2C address of the device with the given index.
+/// - `index`: 0-3, as set in the follower's II settings.
+///
+/// Returns `None` if `index` is out of range.
+pub const fn address(index: u8) -> Option<u8> {
+    if index <= 3 {
+        Some(BASE_ADDRESS + index)
+    } else {
+        None
+    }
+}
+
+/// Metadata describing a Teletype follower.
+pub const DESCRIPTOR: DeviceDescriptor = DeviceDescriptor {
+    name: "Teletype",
+    default_address: BASE_ADDRESS,
+    address_range: BASE_ADDRESS..=BASE_ADDRESS + 3,
+    max_command_length: <Commands as Command>::MAX_LENGTH,
+    has_cv: false,
+    has_gates: false,
+    has_osc: false,
+    has_envelope: false,
+    cv_channels: 0,
+    gate_channels: 0,
+};
+
+/// All supported II commands for a Teletype follower.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Commands {
+    /// `TT.SCRIPT`: Runs a script immediately, as if `SCRIPT` were called on the follower.
+    /// - `script`: 1-8 for the numbered scripts, 9 for the metro script, 10 for init.
+    SetScript { script: u8 },
+    /// `TT.TR`: Fires a trigger input on the follower, which runs the matching script
+    /// unless that input is muted there.
+    /// - `script`: 1-8
+    SetTrigger { script: u8 },
+    /// `TT.P`: Sets a value in the follower's working pattern.
+    /// - `index`: 0-63
+    /// - `value`: A signed 16-bit value.
+    SetPattern { index: u8, value: i16 },
+}
+
+impl Commands {
+    /// Returns the opcode this command writes as its first byte.
+    pub const fn opcode(&self) -> u8 {
+        match self {
+            Self::SetScript { .. } => 0x00,
+            Self::SetTrigger { .. } => 0x01,
+            Self::SetPattern { .. } => 0x02,
+        }
+    }
+
+    /// Returns the category of the command, e.g. for prioritizing II traffic.
+    pub const fn category(&self) -> CommandCategory {
+        match self {
+            Self::SetTrigger { .. } => CommandCategory::Gate,
+            Self::SetScript { .. } | Self::SetPattern { .. } => CommandCategory::Mode,
+        }
+    }
+
+    /// Returns the equivalent Teletype `TT.*` op. Every command has one.
+    pub const fn teletype_op(&self) -> Option<TeletypeOp<Self>> {
+        Some(TeletypeOp(*self))
+    }
+
+    /// Returns the key identifying the destination the command writes to, see
+    /// [`TargetKey`].
+    ///
+    /// Returns `None` for `SetScript` and `SetTrigger`, which run a script on every send.
+    pub const fn target_key(&self) -> Option<TargetKey> {
+        match *self {
+            Self::SetScript { .. } | Self::SetTrigger { .. } => None,
+            Self::SetPattern { index, .. } => Some(TargetKey::new(self.opcode(), index)),
+        }
+    }
+
+    /// Serializes the command into a fixed-size array, usable in `const` context.
+    ///
+    /// Returns the array and the number of valid bytes at its start. Unlike
+    /// [`Command::to_bytes`], this does not check field ranges.
+    pub const fn to_bytes_array(&self) -> ([u8; <Self as Command>::MAX_LENGTH], usize) {
+        let mut buffer = [0u8; <Self as Command>::MAX_LENGTH];
+        match self.encode(&mut buffer) {
+            Ok(len) => (buffer, len),
+            Err(_) => panic!("MAX_LENGTH is shorter than the command"),
+        }
+    }
+
+    /// Writes the command to the start of `buffer` and returns the number of bytes written.
+    const fn encode(&self, buffer: &mut [u8]) -> Result<usize, SerializationError> {
+        match *self {
+            Self::SetScript { script } | Self::SetTrigger { script } => {
+                if buffer.len() < 2 {
+                    return Err(SerializationError::buffer_too_small(2, buffer.len()));
+                }
+                buffer[0] = self.opcode();
+                buffer[1] = script;
+                Ok(2)
+            }
+            Self::SetPattern { index, value } => {
+                if buffer.len() < 4 {
+                    return Err(SerializationError::buffer_too_small(4, buffer.len()));
+                }
+                buffer[0] = self.opcode();
+                buffer[1] = index;
+                put_i16_be(buffer, 2, value);
+                Ok(4)
+            }
+        }
+    }
+
+    /// Checks the fields against the ranges documented on each variant.
+    fn validate(&self) -> Result<(), SerializationError> {
+        match *self {
+            Self::SetScript { script } => check_range("script", script, 1..=10),
+            Self::SetTrigger { script } => check_range("script", script, 1..=8),
+            Self::SetPattern { index, .. } => check_range("index", index, 0..=63),
+        }
+    }
+}
+
+/// Formats the command as a Teletype-style mnemonic followed by its fields in declaration
+/// order, separated by spaces. Booleans are written as 0 or 1.
+///
+/// The mnemonics are stable, so tools may parse them from logs:
+///
+/// | Command | Mnemonic |
+/// |---------|----------|
+/// | `SetScript` | `TT.SCRIPT` |
+/// | `SetTrigger` | `TT.TR` |
+/// | `SetPattern` | `TT.P` |
+impl fmt::Display for Commands {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::SetScript { script } => write!(f, "TT.SCRIPT {script}"),
+            Self::SetTrigger { script } => write!(f, "TT.TR {script}"),
+            Self::SetPattern { index, value } => write!(f, "TT.P {index} {value}"),
+        }
+    }
+}
+
+/// Formats the command as its Teletype `TT.*` op, which matches its `Display` output.
+impl fmt::Display for TeletypeOp<Commands> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl Command for Commands {
+    const MAX_LENGTH: usize = 4; // SetPattern is the longest.
+
+    fn to_bytes<'a>(&self, buffer: &'a mut [u8]) -> Result<&'a [u8], SerializationError> {
+        #[cfg(feature = "strict")]
+        self.validate()?;
+        #[cfg(not(feature = "strict"))]
+        debug_assert!(self.validate().is_ok(), "field out of its documented range");
+
+        let len = self.encode(buffer)?;
+        Ok(&buffer[..len])
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            Self::SetScript { .. } | Self::SetTrigger { .. } => 2,
+            Self::SetPattern { .. } => 4,
+        }
+    }
+
+    fn address(&self, index: u8) -> Option<u8> {
+        address(index)
+    }
+}
+
+impl Parse for Commands {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, usize), DeserializationError> {
+        let (&opcode, payload) = bytes
+            .split_first()
+            .ok_or(DeserializationError::UnexpectedEof)?;
+        let command = match (opcode, payload) {
+            (0x00, &[script, ..]) => Self::SetScript { script },
+            (0x01, &[script, ..]) => Self::SetTrigger { script },
+            (0x02, &[index, hi, lo, ..]) => Self::SetPattern {
+                index,
+                value: i16::from_be_bytes([hi, lo]),
+            },
+            (0x00..=0x02, _) => return Err(DeserializationError::UnexpectedEof),
+            (opcode, _) => return Err(DeserializationError::UnknownOpcode(opcode)),
+        };
+        Ok((command, command.len()))
+    }
+}
@@ -0,0 +1,395 @@
+//! Commands for the BPC Telexi (TXi).
+//! The final address is the `BASE_ADDRESS` plus the device index (0-7), which
+//! [`address`] calculates.
+//!
+//! The TXi is an input expander: most of its `TI.*` ops are reads that the leader polls.
+//! Polling takes two steps. First write `RequestInput` or `RequestParam` to select the
+//! value, then issue your own I2C read of 2 bytes from the same address; this crate only
+//! serializes the write. The other commands are write-only configuration: the scale and
+//! map commands shape the values later read back, and the TXi sends no reply to them.
+//!
+//! The following read ops are not covered yet:
+//! - `TI.IN.N` (`0x02`): reads an input as a quantized note number.
+//! - `TI.PARAM.N` (`0x12`): reads a parameter knob as a quantized note number.
+
+use core::fmt;
+
+use crate::{
+    Command, CommandCategory, DeserializationError, DeviceDescriptor, Parse, SerializationError,
+    TargetKey, TeletypeOp, check_range, put_i16_be,
+};
+
+/// The base I2C address for TXi modules.
+pub const BASE_ADDRESS: u8 = 0x68;
+
+/// Resolves the I2C address of the device with the given index.
+/// - `index`: 0-7, as set on the module's address jumpers.
+///
+/// Returns `None` if `index` is out of range.
+pub const fn address(index: u8) -> Option<u8> {
+    if index <= 7 {
+        Some(BASE_ADDRESS + index)
+    } else {
+        None
+    }
+}
+
+/// Metadata describing the Telexi.
+pub const DESCRIPTOR: DeviceDescriptor = DeviceDescriptor {
+    name: "Telexi",
+    default_address: BASE_ADDRESS,
+    address_range: BASE_ADDRESS..=BASE_ADDRESS + 7,
+    max_command_length: <Commands as Command>::MAX_LENGTH,
+    has_cv: false,
+    has_gates: false,
+    has_osc: false,
+    has_envelope: false,
+    cv_channels: 0,
+    gate_channels: 0,
+};
+
+/// All supported II commands for the Telexi.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Commands {
+    /// `TI.IN.SCALE`: Scales the value read back from an input to `min..=max`.
+    /// - `port`: 0-3
+    /// - `min`: A signed 16-bit lower bound for the scaled value.
+    /// - `max`: A signed 16-bit upper bound for the scaled value.
+    SetInputScale { port: u8, min: i16, max: i16 },
+    /// `TI.IN.MAP`: Maps the voltage window of an input to the given bounds.
+    /// - `port`: 0-3
+    /// - `top`: A signed 16-bit value read back at the top of the window.
+    /// - `bottom`: A signed 16-bit value read back at the bottom of the window.
+    SetInputMap { port: u8, top: i16, bottom: i16 },
+    /// `TI.PARAM.QT`: Selects the scale a parameter knob is quantized to.
+    /// - `port`: 0-3
+    /// - `scale`: The index of the scale to quantize to.
+    SetParamQuantize { port: u8, scale: u8 },
+    /// `TI.PARAM.SCALE`: Scales the value read back from a parameter knob to `min..=max`.
+    /// - `port`: 0-3
+    /// - `min`: A signed 16-bit lower bound for the scaled value.
+    /// - `max`: A signed 16-bit upper bound for the scaled value.
+    SetParamScale { port: u8, min: i16, max: i16 },
+    /// `TI.PARAM.MAP`: Maps the travel of a parameter knob to the given bounds.
+    /// - `port`: 0-3
+    /// - `top`: A signed 16-bit value read back with the knob fully clockwise.
+    /// - `bottom`: A signed 16-bit value read back with the knob fully counterclockwise.
+    SetParamMap { port: u8, top: i16, bottom: i16 },
+    /// `TI.STORE`: Stores the current configuration to the module's flash.
+    Store,
+    /// `TI.RESET`: Resets the configuration to its factory defaults.
+    Reset,
+    /// `TI.IN`: Selects an input to be read. Follow it with an I2C read of 2 bytes from
+    /// the same address, which returns the input value as a big-endian `i16`.
+    /// - `port`: 0-3
+    RequestInput { port: u8 },
+    /// `TI.PARAM`: Selects a parameter knob to be read. Follow it with an I2C read of 2
+    /// bytes from the same address, which returns the knob value as a big-endian `i16`.
+    /// - `port`: 0-3
+    RequestParam { port: u8 },
+}
+
+impl Commands {
+    /// Returns the opcode this command writes as its first byte.
+    pub const fn opcode(&self) -> u8 {
+        match self {
+            Self::SetInputScale { .. } => 0x03,
+            Self::SetInputMap { .. } => 0x04,
+            Self::SetParamQuantize { .. } => 0x11,
+            Self::SetParamScale { .. } => 0x13,
+            Self::SetParamMap { .. } => 0x14,
+            Self::Store => 0x20,
+            Self::Reset => 0x21,
+            Self::RequestInput { .. } => 0x00,
+            Self::RequestParam { .. } => 0x10,
+        }
+    }
+
+    /// Returns the category of the command, e.g. for prioritizing II traffic.
+    pub const fn category(&self) -> CommandCategory {
+        match self {
+            Self::Store | Self::Reset => CommandCategory::Preset,
+            Self::SetInputScale { .. }
+            | Self::SetParamMap { .. }
+            | Self::SetParamScale { .. }
+            | Self::SetInputMap { .. }
+            | Self::SetParamQuantize { .. } => CommandCategory::Mode,
+            Self::RequestInput { .. } | Self::RequestParam { .. } => CommandCategory::Cv,
+        }
+    }
+
+    /// Returns the equivalent Teletype `TI.*` op.
+    ///
+    /// Returns `None` for `Store` and `Reset`, whose Teletype ops also take the number of
+    /// the TXi, which the command does not carry.
+    pub const fn teletype_op(&self) -> Option<TeletypeOp<Self>> {
+        match self {
+            Self::Store | Self::Reset => None,
+            _ => Some(TeletypeOp(*self)),
+        }
+    }
+
+    /// Returns the key identifying the destination the command writes to, see
+    /// [`TargetKey`].
+    ///
+    /// Returns `None` for `Store`, `Reset` and the read requests, which act on every send.
+    pub const fn target_key(&self) -> Option<TargetKey> {
+        match *self {
+            Self::SetInputScale { port, .. }
+            | Self::SetInputMap { port, .. }
+            | Self::SetParamQuantize { port, .. }
+            | Self::SetParamScale { port, .. }
+            | Self::SetParamMap { port, .. } => Some(TargetKey::new(self.opcode(), port)),
+            Self::Store | Self::Reset | Self::RequestInput { .. } | Self::RequestParam { .. } => {
+                None
+            }
+        }
+    }
+
+    /// Serializes the command into a fixed-size array, usable in `const` context.
+    ///
+    /// Returns the array and the number of valid bytes at its start. Unlike
+    /// [`Command::to_bytes`], this does not check field ranges.
+    pub const fn to_bytes_array(&self) -> ([u8; <Self as Command>::MAX_LENGTH], usize) {
+        let mut buffer = [0u8; <Self as Command>::MAX_LENGTH];
+        match self.encode(&mut buffer) {
+            Ok(len) => (buffer, len),
+            Err(_) => panic!("MAX_LENGTH is shorter than the command"),
+        }
+    }
+
+    /// Writes the command to the start of `buffer` and returns the number of bytes written.
+    const fn encode(&self, buffer: &mut [u8]) -> Result<usize, SerializationError> {
+        match *self {
+            Self::SetInputScale { port, min, max } => {
+                if buffer.len() < 6 {
+                    return Err(SerializationError::buffer_too_small(6, buffer.len()));
+                }
+                buffer[0] = self.opcode();
+                buffer[1] = port;
+                put_i16_be(buffer, 2, min);
+                put_i16_be(buffer, 4, max);
+                Ok(6)
+            }
+            Self::SetInputMap { port, top, bottom } => {
+                if buffer.len() < 6 {
+                    return Err(SerializationError::buffer_too_small(6, buffer.len()));
+                }
+                buffer[0] = self.opcode();
+                buffer[1] = port;
+                put_i16_be(buffer, 2, top);
+                put_i16_be(buffer, 4, bottom);
+                Ok(6)
+            }
+            Self::SetParamQuantize { port, scale } => {
+                if buffer.len() < 3 {
+                    return Err(SerializationError::buffer_too_small(3, buffer.len()));
+                }
+                buffer[0] = self.opcode();
+                buffer[1] = port;
+                buffer[2] = scale;
+                Ok(3)
+            }
+            Self::SetParamScale { port, min, max } => {
+                if buffer.len() < 6 {
+                    return Err(SerializationError::buffer_too_small(6, buffer.len()));
+                }
+                buffer[0] = self.opcode();
+                buffer[1] = port;
+                put_i16_be(buffer, 2, min);
+                put_i16_be(buffer, 4, max);
+                Ok(6)
+            }
+            Self::SetParamMap { port, top, bottom } => {
+                if buffer.len() < 6 {
+                    return Err(SerializationError::buffer_too_small(6, buffer.len()));
+                }
+                buffer[0] = self.opcode();
+                buffer[1] = port;
+                put_i16_be(buffer, 2, top);
+                put_i16_be(buffer, 4, bottom);
+                Ok(6)
+            }
+            Self::Store => {
+                if buffer.is_empty() {
+                    return Err(SerializationError::buffer_too_small(1, 0));
+                }
+                buffer[0] = self.opcode();
+                Ok(1)
+            }
+            Self::Reset => {
+                if buffer.is_empty() {
+                    return Err(SerializationError::buffer_too_small(1, 0));
+                }
+                buffer[0] = self.opcode();
+                Ok(1)
+            }
+            Self::RequestInput { port } => {
+                if buffer.len() < 2 {
+                    return Err(SerializationError::buffer_too_small(2, buffer.len()));
+                }
+                buffer[0] = self.opcode();
+                buffer[1] = port;
+                Ok(2)
+            }
+            Self::RequestParam { port } => {
+                if buffer.len() < 2 {
+                    return Err(SerializationError::buffer_too_small(2, buffer.len()));
+                }
+                buffer[0] = self.opcode();
+                buffer[1] = port;
+                Ok(2)
+            }
+        }
+    }
+
+    /// Checks the fields against the ranges documented on each variant.
+    fn validate(&self) -> Result<(), SerializationError> {
+        match *self {
+            Self::SetInputScale { port, .. }
+            | Self::SetParamScale { port, .. }
+            | Self::SetInputMap { port, .. }
+            | Self::SetParamMap { port, .. }
+            | Self::SetParamQuantize { port, .. }
+            | Self::RequestInput { port }
+            | Self::RequestParam { port } => check_range("port", port, 0..=3),
+            Self::Store | Self::Reset => Ok(()),
+        }
+    }
+}
+
+/// Formats the command as a Teletype-style mnemonic followed by its fields in declaration
+/// order, separated by spaces. Booleans are written as 0 or 1.
+///
+/// The mnemonics are stable, so tools may parse them from logs:
+///
+/// | Command | Mnemonic |
+/// |---------|----------|
+/// | `SetInputScale` | `TI.IN.SCALE` |
+/// | `SetInputMap` | `TI.IN.MAP` |
+/// | `SetParamQuantize` | `TI.PARAM.QT` |
+/// | `SetParamScale` | `TI.PARAM.SCALE` |
+/// | `SetParamMap` | `TI.PARAM.MAP` |
+/// | `Store` | `TI.STORE` |
+/// | `Reset` | `TI.RESET` |
+/// | `RequestInput` | `TI.IN` |
+/// | `RequestParam` | `TI.PARAM` |
+impl fmt::Display for Commands {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::SetInputScale { port, min, max } => write!(f, "TI.IN.SCALE {port} {min} {max}"),
+            Self::SetInputMap { port, top, bottom } => write!(f, "TI.IN.MAP {port} {top} {bottom}"),
+            Self::SetParamQuantize { port, scale } => write!(f, "TI.PARAM.QT {port} {scale}"),
+            Self::SetParamScale { port, min, max } => {
+                write!(f, "TI.PARAM.SCALE {port} {min} {max}")
+            }
+            Self::SetParamMap { port, top, bottom } => {
+                write!(f, "TI.PARAM.MAP {port} {top} {bottom}")
+            }
+            Self::Store => f.write_str("TI.STORE"),
+            Self::Reset => f.write_str("TI.RESET"),
+            Self::RequestInput { port } => write!(f, "TI.IN {port}"),
+            Self::RequestParam { port } => write!(f, "TI.PARAM {port}"),
+        }
+    }
+}
+
+/// Formats the command as its Teletype `TI.*` op, which counts ports from 1. Ports are
+/// numbered as on the first TXi.
+impl fmt::Display for TeletypeOp<Commands> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Commands::SetInputScale { port, min, max } => {
+                write!(f, "TI.IN.SCALE {} {min} {max}", u16::from(port) + 1)
+            }
+            Commands::SetInputMap { port, top, bottom } => {
+                write!(f, "TI.IN.MAP {} {top} {bottom}", u16::from(port) + 1)
+            }
+            Commands::SetParamQuantize { port, scale } => {
+                write!(f, "TI.PARAM.QT {} {scale}", u16::from(port) + 1)
+            }
+            Commands::SetParamScale { port, min, max } => {
+                write!(f, "TI.PARAM.SCALE {} {min} {max}", u16::from(port) + 1)
+            }
+            Commands::SetParamMap { port, top, bottom } => {
+                write!(f, "TI.PARAM.MAP {} {top} {bottom}", u16::from(port) + 1)
+            }
+            Commands::RequestInput { port } => write!(f, "TI.IN {}", u16::from(port) + 1),
+            Commands::RequestParam { port } => write!(f, "TI.PARAM {}", u16::from(port) + 1),
+            // `Store` and `Reset` have no op, so they are never wrapped.
+            command => fmt::Display::fmt(&command, f),
+        }
+    }
+}
+
+impl Command for Commands {
+    const MAX_LENGTH: usize = 6; // The scale and map commands are the longest.
+
+    fn to_bytes<'a>(&self, buffer: &'a mut [u8]) -> Result<&'a [u8], SerializationError> {
+        #[cfg(feature = "strict")]
+        self.validate()?;
+        #[cfg(not(feature = "strict"))]
+        debug_assert!(self.validate().is_ok(), "field out of its documented range");
+
+        let len = self.encode(buffer)?;
+        Ok(&buffer[..len])
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            Self::Store | Self::Reset => 1,
+            Self::RequestInput { .. } | Self::RequestParam { .. } => 2,
+            Self::SetParamQuantize { .. } => 3,
+            Self::SetInputScale { .. }
+            | Self::SetParamScale { .. }
+            | Self::SetInputMap { .. }
+            | Self::SetParamMap { .. } => 6,
+        }
+    }
+
+    fn address(&self, index: u8) -> Option<u8> {
+        address(index)
+    }
+}
+
+impl Parse for Commands {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, usize), DeserializationError> {
+        let (&opcode, payload) = bytes
+            .split_first()
+            .ok_or(DeserializationError::UnexpectedEof)?;
+        let command = match (opcode, payload) {
+            (0x03, &[port, min_hi, min_lo, max_hi, max_lo, ..]) => Self::SetInputScale {
+                port,
+                min: i16::from_be_bytes([min_hi, min_lo]),
+                max: i16::from_be_bytes([max_hi, max_lo]),
+            },
+            (0x04, &[port, top_hi, top_lo, bottom_hi, bottom_lo, ..]) => Self::SetInputMap {
+                port,
+                top: i16::from_be_bytes([top_hi, top_lo]),
+                bottom: i16::from_be_bytes([bottom_hi, bottom_lo]),
+            },
+            (0x11, &[port, scale, ..]) => Self::SetParamQuantize { port, scale },
+            (0x13, &[port, min_hi, min_lo, max_hi, max_lo, ..]) => Self::SetParamScale {
+                port,
+                min: i16::from_be_bytes([min_hi, min_lo]),
+                max: i16::from_be_bytes([max_hi, max_lo]),
+            },
+            (0x14, &[port, top_hi, top_lo, bottom_hi, bottom_lo, ..]) => Self::SetParamMap {
+                port,
+                top: i16::from_be_bytes([top_hi, top_lo]),
+                bottom: i16::from_be_bytes([bottom_hi, bottom_lo]),
+            },
+            (0x20, _) => Self::Store,
+            (0x21, _) => Self::Reset,
+            (0x00, &[port, ..]) => Self::RequestInput { port },
+            (0x10, &[port, ..]) => Self::RequestParam { port },
+            (0x00 | 0x03 | 0x04 | 0x10 | 0x11 | 0x13 | 0x14, _) => {
+                return Err(DeserializationError::UnexpectedEof);
+            }
+            (opcode, _) => return Err(DeserializationError::UnknownOpcode(opcode)),
+        };
+        Ok((command, command.len()))
+    }
+}
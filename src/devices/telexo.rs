@@ -1,105 +1,1148 @@
 //! Commands for the BPC Telexo (TXo).
-//! The application is responsible for calculating the final address by adding a device
-//! index (0-7) to the `BASE_ADDRESS`.
+//! The final address is the `BASE_ADDRESS` plus the device index (0-7), which
+//! [`address`] calculates.
 
-use crate::{Command, SerializationError};
+use core::fmt;
+
+use crate::units::{Bpm, Millis, QuantizedNote, RawCv};
+use crate::{
+    Command, CommandCategory, DeserializationError, DeviceDescriptor, Parse, SerializationError,
+    TargetKey, TeletypeOp, check_range, parse_bool, put_i16_be, put_u16_be,
+};
 
 /// The base I2C address for TXo modules.
 pub const BASE_ADDRESS: u8 = 0x60;
 
+/// The `dest` value that stops an envelope from firing a trigger output.
+pub const ENVELOPE_TRIGGER_OFF: u8 = 0xFF;
+
+/// Resolves the I2C address of the device with the given index.
+/// - `index`: 0-7, as set on the module's address jumpers.
+///
+/// Returns `None` if `index` is out of range.
+pub const fn address(index: u8) -> Option<u8> {
+    if index <= 7 {
+        Some(BASE_ADDRESS + index)
+    } else {
+        None
+    }
+}
+
+/// Metadata describing the Telexo.
+pub const DESCRIPTOR: DeviceDescriptor = DeviceDescriptor {
+    name: "Telexo",
+    default_address: BASE_ADDRESS,
+    address_range: BASE_ADDRESS..=BASE_ADDRESS + 7,
+    max_command_length: <Commands as Command>::MAX_LENGTH,
+    has_cv: true,
+    has_gates: true,
+    has_osc: true,
+    has_envelope: true,
+    cv_channels: 4,
+    gate_channels: 4,
+};
+
+/// Builds the commands that glide a CV output to `target` over `slew_ms` milliseconds.
+///
+/// The slew time only applies to CV changes sent after it, so the returned `SetCvSlew`
+/// must be sent before the `SetCv`.
+pub const fn glide(port: u8, target: i16, slew_ms: u16) -> [Commands; 2] {
+    [
+        Commands::SetCvSlew { port, ms: slew_ms },
+        Commands::SetCv {
+            port,
+            value: target,
+        },
+    ]
+}
+
 /// All supported II commands for the Telexo.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Commands {
     /// `set gate`: Sets the state of a gate output.
     /// - `port`: 0-3
     /// - `state`: `true` for high (1), `false` for low (0).
     SetGate { port: u8, state: bool },
-    /// `set CV`: Sets the CV for a given output port.
+    /// `TO.TR.TOG`: Toggles the state of a trigger output.
+    /// - `port`: 0-3
+    SetTrToggle { port: u8 },
+    /// `TO.TR.PULSE`: Sends a pulse to a trigger output.
+    /// - `port`: 0-3
+    SetTrPulse { port: u8 },
+    /// `TO.TR.TIME`: Sets the pulse duration for a trigger output.
+    /// - `port`: 0-3
+    /// - `ms`: The pulse duration in milliseconds as an unsigned 16-bit integer.
+    SetTrTime { port: u8, ms: u16 },
+    /// `TO.TR.POL`: Sets the polarity of a trigger output.
+    /// - `port`: 0-3
+    /// - `polarity`: `true` for active high (1), `false` for inverted (0).
+    SetTrPolarity { port: u8, polarity: bool },
+    /// `set CV`: Sets the CV for a given output port. The output moves to the new value
+    /// over the slew time configured with `SetCvSlew` (opcode `0x11`).
     /// - `port`: 0-3
     /// - `value`: A signed 16-bit integer representing voltage.
     SetCv { port: u8, value: i16 },
+    /// `set CV immediate` (`TO.CV.SET`): Jumps a CV output to a value, ignoring and
+    /// cancelling any slew in progress (opcode `0x10`). Use this instead of `SetCv`
+    /// when a retrigger must land exactly on the new value.
+    /// - `port`: 0-3
+    /// - `value`: A signed 16-bit integer representing voltage.
+    SetCvImmediate { port: u8, value: i16 },
     /// `set CV slew`: Sets the slew time for a given CV output.
     /// - `port`: 0-3
     /// - `ms`: The slew time in milliseconds as an unsigned 16-bit integer.
     SetCvSlew { port: u8, ms: u16 },
+    /// `TO.CV.OFF`: Sets a static offset that is added to a CV output.
+    /// - `port`: 0-3
+    /// - `value`: A signed 16-bit offset, in the same units as `SetCv`.
+    SetCvOffset { port: u8, value: i16 },
+    /// `TO.CV.QT`: Sets a CV output to a note, quantized to the output's scale.
+    /// - `port`: 0-3
+    /// - `note`: The note index within the scale.
+    SetCvQuantizedNote { port: u8, note: u8 },
+    /// `TO.CV.SCALE`: Selects the scale `SetCvQuantizedNote` quantizes a CV output to.
+    /// - `port`: 0-3
+    /// - `scale`: 0-15, e.g. 0 for equal temperament (the default), 1 for Pythagorean,
+    ///   2 for Vallotti and 3 for Werckmeister III. See the TXo manual for the full list.
+    SetCvScale { port: u8, scale: u8 },
     /// `set osc pitch`: Sets the oscillator pitch.
     /// - `port`: 0-3
     /// - `pitch`: A signed 16-bit integer pitch value.
     SetOscPitch { port: u8, pitch: i16 },
+    /// `TO.OSC.N`: Sets the oscillator pitch as a semitone index.
+    /// - `port`: 0-3
+    /// - `note`: 0-127, in semitones above the oscillator's 0V pitch.
+    SetOscNote { port: u8, note: u8 },
+    /// `TO.OSC.SYNC`: Resets the phase of an oscillator to the start of its cycle.
+    /// - `port`: 0-3
+    OscSync { port: u8 },
+    /// `TO.OSC.PHASE`: Sets the phase of an oscillator.
+    /// - `port`: 0-3
+    /// - `phase`: An unsigned 16-bit position within one cycle, where 0 is the start and
+    ///   65535 is just before the end of the cycle.
+    SetOscPhase { port: u8, phase: u16 },
+    /// `TO.OSC.LFO`: Puts an oscillator into LFO mode, so it runs at sub-audio rates.
+    /// - `port`: 0-3
+    /// - `enabled`: `true` to enable LFO mode, `false` to return to audio rates.
+    SetOscLfoMode { port: u8, enabled: bool },
+    /// `TO.OSC.LFO.RATE`: Sets the rate of an oscillator in LFO mode.
+    /// - `port`: 0-3
+    /// - `rate`: A signed 16-bit rate, bipolar around a center of 0: positive values run
+    ///   the LFO forwards and negative values in reverse, with the magnitude setting the speed.
+    SetOscLfoRate { port: u8, rate: i16 },
+    /// `TO.OSC.FQ`: Sets the oscillator frequency directly.
+    /// - `port`: 0-3
+    /// - `freq`: The frequency in Hz as an unsigned 16-bit integer.
+    SetOscFreq { port: u8, freq: u16 },
+    /// `TO.OSC.SLEW`: Sets the time an oscillator takes to glide to a new pitch (portamento).
+    /// - `port`: 0-3
+    /// - `ms`: The slew time in milliseconds as an unsigned 16-bit integer. 0 disables slewing.
+    SetOscSlew { port: u8, ms: u16 },
     /// `set osc waveform`: Sets the oscillator waveform.
     /// - `port`: 0-3
     /// - `waveform`: An unsigned 16-bit value (0-5000).
     SetOscWaveform { port: u8, waveform: u16 },
+    /// `TO.OSC.WIDTH`: Sets the pulse width of the oscillator's square wave.
+    /// - `port`: 0-3
+    /// - `width`: The duty cycle in percent (0-100) as an unsigned 16-bit integer.
+    SetOscWidth { port: u8, width: u16 },
+    /// `TO.OSC.RECT`: Sets the rectification applied to the oscillator output.
+    /// - `port`: 0-3
+    /// - `mode`: -3 to 3. Negative values rectify the negative half, positive values
+    ///   the positive half, with larger magnitudes moving from half-wave to full-wave.
+    ///   0 disables rectification.
+    SetOscRectify { port: u8, mode: i8 },
+    /// `TO.OSC.CTR`: Sets the center pitch that an oscillator is tuned around, for fine
+    /// detuning stacked oscillators.
+    /// - `port`: 0-3
+    /// - `value`: A signed 16-bit offset in the same units as `SetOscPitch`. 0 is neutral,
+    ///   leaving the oscillator at its set pitch.
+    SetOscCenter { port: u8, value: i16 },
     /// `set envelope mode`: Enables or disables envelope mode for an output.
     /// - `port`: 0-3
     /// - `enabled`: `true` to enable, `false` to disable.
     SetEnvelopeMode { port: u8, enabled: bool },
+    /// `TO.ENV.ATT`: Sets the envelope attack time.
+    /// - `port`: 0-3
+    /// - `ms`: The attack time in milliseconds as an unsigned 16-bit integer.
+    SetEnvelopeAttack { port: u8, ms: u16 },
+    /// `TO.ENV.DEC`: Sets the envelope decay time.
+    /// - `port`: 0-3
+    /// - `ms`: The decay time in milliseconds as an unsigned 16-bit integer.
+    SetEnvelopeDecay { port: u8, ms: u16 },
+    /// `TO.ENV.EOR`: Fires a trigger output when the envelope finishes rising.
+    /// - `port`: 0-3
+    /// - `dest`: The index of the trigger output to fire, or [`ENVELOPE_TRIGGER_OFF`] to disable.
+    SetEnvelopeEor { port: u8, dest: u8 },
+    /// `TO.ENV.EOC`: Fires a trigger output when the envelope completes its cycle.
+    /// - `port`: 0-3
+    /// - `dest`: The index of the trigger output to fire, or [`ENVELOPE_TRIGGER_OFF`] to disable.
+    SetEnvelopeEoc { port: u8, dest: u8 },
     /// `set envelope`: Triggers the envelope on or off.
     /// - `port`: 0-3
     /// - `on`: `true` for on, `false` for off.
     SetEnvelopeState { port: u8, on: bool },
+    /// `TO.M`: Sets the period of the internal metronome.
+    /// - `ms`: The period in milliseconds as an unsigned 16-bit integer.
+    SetMetroTime { ms: u16 },
+    /// `TO.M.ACT`: Starts or halts the internal metronome.
+    /// - `state`: `true` to run (1), `false` (0) halts the metronome.
+    SetMetroActive { state: bool },
+    /// `TO.M.BPM`: Sets the period of the internal metronome in beats per minute.
+    /// - `bpm`: The tempo as an unsigned 16-bit integer.
+    SetMetroBpm { bpm: u16 },
+    /// `TO.KILL`: Cancels all pending trigger pulses and CV slews, e.g. to silence the
+    /// module on stop.
+    Kill,
+    /// `TO.INIT`: Resets every output and setting of the module to its power-on default.
+    Reset,
 }
 
-impl Command for Commands {
-    const MAX_LENGTH: usize = 4; // All listed commands are 3 or 4 bytes long.
+impl Commands {
+    /// Builds a `SetCv` command from a raw CV value.
+    pub const fn set_cv(port: u8, value: RawCv) -> Self {
+        Self::SetCv {
+            port,
+            value: value.0,
+        }
+    }
 
-    fn to_bytes<'a>(&self, buffer: &'a mut [u8]) -> Result<&'a [u8], SerializationError> {
-        // A single check for the max required length simplifies the match arms.
-        if buffer.len() < Self::MAX_LENGTH {
-            return Err(SerializationError::BufferTooSmall);
+    /// Builds a `SetCvImmediate` command from a raw CV value.
+    pub const fn set_cv_immediate(port: u8, value: RawCv) -> Self {
+        Self::SetCvImmediate {
+            port,
+            value: value.0,
         }
+    }
+
+    /// Builds a `SetCvOffset` command from a raw CV value.
+    pub const fn set_cv_offset(port: u8, value: RawCv) -> Self {
+        Self::SetCvOffset {
+            port,
+            value: value.0,
+        }
+    }
+
+    /// Builds a `SetCvQuantizedNote` command from a note index within the output's scale.
+    pub const fn set_cv_quantized_note(port: u8, note: QuantizedNote) -> Self {
+        Self::SetCvQuantizedNote { port, note: note.0 }
+    }
+
+    /// Builds a `SetTrTime` command from a pulse duration.
+    pub const fn set_tr_time(port: u8, duration: Millis) -> Self {
+        Self::SetTrTime {
+            port,
+            ms: duration.0,
+        }
+    }
+
+    /// Builds a `SetCvSlew` command from a slew time.
+    pub const fn set_cv_slew(port: u8, duration: Millis) -> Self {
+        Self::SetCvSlew {
+            port,
+            ms: duration.0,
+        }
+    }
 
+    /// Builds a `SetOscSlew` command from a slew time.
+    pub const fn set_osc_slew(port: u8, duration: Millis) -> Self {
+        Self::SetOscSlew {
+            port,
+            ms: duration.0,
+        }
+    }
+
+    /// Builds a `SetEnvelopeAttack` command from an attack time.
+    pub const fn set_envelope_attack(port: u8, duration: Millis) -> Self {
+        Self::SetEnvelopeAttack {
+            port,
+            ms: duration.0,
+        }
+    }
+
+    /// Builds a `SetEnvelopeDecay` command from a decay time.
+    pub const fn set_envelope_decay(port: u8, duration: Millis) -> Self {
+        Self::SetEnvelopeDecay {
+            port,
+            ms: duration.0,
+        }
+    }
+
+    /// Builds a `SetMetroTime` command from a metronome period.
+    pub const fn set_metro_time(period: Millis) -> Self {
+        Self::SetMetroTime { ms: period.0 }
+    }
+
+    /// Builds a `SetMetroBpm` command from a tempo.
+    pub const fn set_metro_bpm(tempo: Bpm) -> Self {
+        Self::SetMetroBpm { bpm: tempo.0 }
+    }
+
+    /// Returns the opcode this command writes as its first byte.
+    pub const fn opcode(&self) -> u8 {
+        match self {
+            Self::SetGate { .. } => 0x00,
+            Self::SetTrToggle { .. } => 0x13,
+            Self::SetTrPulse { .. } => 0x14,
+            Self::SetTrTime { .. } => 0x15,
+            Self::SetTrPolarity { .. } => 0x16,
+            Self::SetCv { .. } => 0x11,
+            Self::SetCvImmediate { .. } => 0x10,
+            Self::SetCvSlew { .. } => 0x12,
+            Self::SetCvOffset { .. } => 0x17,
+            Self::SetCvQuantizedNote { .. } => 0x18,
+            Self::SetCvScale { .. } => 0x19,
+            Self::SetOscPitch { .. } => 0x41,
+            Self::SetOscNote { .. } => 0x42,
+            Self::OscSync { .. } => 0x43,
+            Self::SetOscPhase { .. } => 0x44,
+            Self::SetOscLfoMode { .. } => 0x45,
+            Self::SetOscLfoRate { .. } => 0x46,
+            Self::SetOscFreq { .. } => 0x48,
+            Self::SetOscSlew { .. } => 0x49,
+            Self::SetOscWaveform { .. } => 0x4A,
+            Self::SetOscWidth { .. } => 0x4B,
+            Self::SetOscRectify { .. } => 0x4C,
+            Self::SetOscCenter { .. } => 0x4D,
+            Self::SetEnvelopeMode { .. } => 0x60,
+            Self::SetEnvelopeAttack { .. } => 0x61,
+            Self::SetEnvelopeDecay { .. } => 0x62,
+            Self::SetEnvelopeEor { .. } => 0x63,
+            Self::SetEnvelopeEoc { .. } => 0x64,
+            Self::SetEnvelopeState { .. } => 0x6D,
+            Self::SetMetroTime { .. } => 0x30,
+            Self::SetMetroActive { .. } => 0x31,
+            Self::SetMetroBpm { .. } => 0x32,
+            Self::Kill => 0x70,
+            Self::Reset => 0x71,
+        }
+    }
+
+    /// Returns the category of the command, e.g. for prioritizing II traffic.
+    pub const fn category(&self) -> CommandCategory {
+        match self {
+            Self::SetCv { .. }
+            | Self::SetCvImmediate { .. }
+            | Self::SetCvSlew { .. }
+            | Self::SetCvOffset { .. }
+            | Self::SetCvQuantizedNote { .. }
+            | Self::SetCvScale { .. } => CommandCategory::Cv,
+            Self::SetGate { .. }
+            | Self::SetTrToggle { .. }
+            | Self::SetTrPulse { .. }
+            | Self::SetTrTime { .. }
+            | Self::SetTrPolarity { .. } => CommandCategory::Gate,
+            Self::SetOscPitch { .. }
+            | Self::SetOscNote { .. }
+            | Self::OscSync { .. }
+            | Self::SetOscPhase { .. }
+            | Self::SetOscLfoMode { .. }
+            | Self::SetOscLfoRate { .. }
+            | Self::SetOscFreq { .. }
+            | Self::SetOscSlew { .. }
+            | Self::SetOscWaveform { .. }
+            | Self::SetOscWidth { .. }
+            | Self::SetOscRectify { .. }
+            | Self::SetOscCenter { .. } => CommandCategory::Osc,
+            Self::SetEnvelopeMode { .. }
+            | Self::SetEnvelopeAttack { .. }
+            | Self::SetEnvelopeDecay { .. }
+            | Self::SetEnvelopeEor { .. }
+            | Self::SetEnvelopeEoc { .. }
+            | Self::SetEnvelopeState { .. } => CommandCategory::Envelope,
+            Self::SetMetroTime { .. }
+            | Self::SetMetroActive { .. }
+            | Self::SetMetroBpm { .. }
+            | Self::Kill => CommandCategory::Transport,
+            Self::Reset => CommandCategory::Preset,
+        }
+    }
+
+    /// Returns the equivalent Teletype `TO.*` op.
+    ///
+    /// Returns `None` for the metronome commands, `Kill` and `Reset`, whose Teletype ops
+    /// also take the number of the TXo, which the command does not carry.
+    pub const fn teletype_op(&self) -> Option<TeletypeOp<Self>> {
+        match self {
+            Self::SetMetroTime { .. }
+            | Self::SetMetroActive { .. }
+            | Self::SetMetroBpm { .. }
+            | Self::Kill
+            | Self::Reset => None,
+            _ => Some(TeletypeOp(*self)),
+        }
+    }
+
+    /// Returns the key identifying the destination the command writes to, see
+    /// [`TargetKey`].
+    ///
+    /// Commands that set the same value in different units share a key: `SetCv`,
+    /// `SetCvImmediate` and `SetCvQuantizedNote` for a CV output, `SetOscPitch`,
+    /// `SetOscNote` and `SetOscFreq` for an oscillator, and `SetMetroTime` and
+    /// `SetMetroBpm` for the metronome. Returns `None` for toggles, pulses, `OscSync`,
+    /// `Kill` and `Reset`, which act on every send.
+    pub const fn target_key(&self) -> Option<TargetKey> {
+        match *self {
+            Self::SetTrToggle { .. }
+            | Self::SetTrPulse { .. }
+            | Self::OscSync { .. }
+            | Self::Kill
+            | Self::Reset => None,
+            // Shared keys use the opcode of the first variant in the arm.
+            Self::SetCv { port, .. }
+            | Self::SetCvImmediate { port, .. }
+            | Self::SetCvQuantizedNote { port, .. } => Some(TargetKey::new(0x11, port)),
+            Self::SetOscPitch { port, .. }
+            | Self::SetOscNote { port, .. }
+            | Self::SetOscFreq { port, .. } => Some(TargetKey::new(0x41, port)),
+            Self::SetMetroTime { .. } | Self::SetMetroBpm { .. } => Some(TargetKey::new(0x30, 0)),
+            Self::SetMetroActive { .. } => Some(TargetKey::new(self.opcode(), 0)),
+            Self::SetGate { port, .. }
+            | Self::SetTrTime { port, .. }
+            | Self::SetTrPolarity { port, .. }
+            | Self::SetCvSlew { port, .. }
+            | Self::SetCvOffset { port, .. }
+            | Self::SetOscPhase { port, .. }
+            | Self::SetOscLfoMode { port, .. }
+            | Self::SetOscLfoRate { port, .. }
+            | Self::SetOscSlew { port, .. }
+            | Self::SetOscWaveform { port, .. }
+            | Self::SetOscWidth { port, .. }
+            | Self::SetOscRectify { port, .. }
+            | Self::SetOscCenter { port, .. }
+            | Self::SetEnvelopeMode { port, .. }
+            | Self::SetEnvelopeAttack { port, .. }
+            | Self::SetEnvelopeDecay { port, .. }
+            | Self::SetEnvelopeEor { port, .. }
+            | Self::SetEnvelopeEoc { port, .. }
+            | Self::SetEnvelopeState { port, .. }
+            | Self::SetCvScale { port, .. } => Some(TargetKey::new(self.opcode(), port)),
+        }
+    }
+
+    /// Serializes the command into a fixed-size array, usable in `const` context.
+    ///
+    /// Returns the array and the number of valid bytes at its start. Unlike
+    /// [`Command::to_bytes`], this does not check field ranges.
+    pub const fn to_bytes_array(&self) -> ([u8; <Self as Command>::MAX_LENGTH], usize) {
+        let mut buffer = [0u8; <Self as Command>::MAX_LENGTH];
+        match self.encode(&mut buffer) {
+            Ok(len) => (buffer, len),
+            Err(_) => panic!("MAX_LENGTH is shorter than the command"),
+        }
+    }
+
+    /// Writes the command to the start of `buffer` and returns the number of bytes written.
+    const fn encode(&self, buffer: &mut [u8]) -> Result<usize, SerializationError> {
         match *self {
             Self::SetGate { port, state } => {
-                buffer[0] = 0x00;
+                if buffer.len() < 3 {
+                    return Err(SerializationError::buffer_too_small(3, buffer.len()));
+                }
+                buffer[0] = self.opcode();
                 buffer[1] = port;
                 buffer[2] = state as u8;
-                Ok(&buffer[..3])
+                Ok(3)
+            }
+            Self::SetTrToggle { port } => {
+                if buffer.len() < 2 {
+                    return Err(SerializationError::buffer_too_small(2, buffer.len()));
+                }
+                buffer[0] = self.opcode();
+                buffer[1] = port;
+                Ok(2)
+            }
+            Self::SetTrPulse { port } => {
+                if buffer.len() < 2 {
+                    return Err(SerializationError::buffer_too_small(2, buffer.len()));
+                }
+                buffer[0] = self.opcode();
+                buffer[1] = port;
+                Ok(2)
+            }
+            Self::SetTrTime { port, ms } => {
+                if buffer.len() < 4 {
+                    return Err(SerializationError::buffer_too_small(4, buffer.len()));
+                }
+                buffer[0] = self.opcode();
+                buffer[1] = port;
+                put_u16_be(buffer, 2, ms);
+                Ok(4)
+            }
+            Self::SetTrPolarity { port, polarity } => {
+                if buffer.len() < 3 {
+                    return Err(SerializationError::buffer_too_small(3, buffer.len()));
+                }
+                buffer[0] = self.opcode();
+                buffer[1] = port;
+                buffer[2] = polarity as u8;
+                Ok(3)
             }
             Self::SetCv { port, value } => {
-                let value_bytes = value.to_be_bytes();
-                buffer[0] = 0x11;
+                if buffer.len() < 4 {
+                    return Err(SerializationError::buffer_too_small(4, buffer.len()));
+                }
+                buffer[0] = self.opcode();
+                buffer[1] = port;
+                put_i16_be(buffer, 2, value);
+                Ok(4)
+            }
+            Self::SetCvImmediate { port, value } => {
+                if buffer.len() < 4 {
+                    return Err(SerializationError::buffer_too_small(4, buffer.len()));
+                }
+                buffer[0] = self.opcode();
                 buffer[1] = port;
-                buffer[2] = value_bytes[0];
-                buffer[3] = value_bytes[1];
-                Ok(&buffer[..4])
+                put_i16_be(buffer, 2, value);
+                Ok(4)
             }
             Self::SetCvSlew { port, ms } => {
-                let ms_bytes = ms.to_be_bytes();
-                buffer[0] = 0x12;
+                if buffer.len() < 4 {
+                    return Err(SerializationError::buffer_too_small(4, buffer.len()));
+                }
+                buffer[0] = self.opcode();
+                buffer[1] = port;
+                put_u16_be(buffer, 2, ms);
+                Ok(4)
+            }
+            Self::SetCvOffset { port, value } => {
+                if buffer.len() < 4 {
+                    return Err(SerializationError::buffer_too_small(4, buffer.len()));
+                }
+                buffer[0] = self.opcode();
                 buffer[1] = port;
-                buffer[2] = ms_bytes[0];
-                buffer[3] = ms_bytes[1];
-                Ok(&buffer[..4])
+                put_i16_be(buffer, 2, value);
+                Ok(4)
+            }
+            Self::SetCvQuantizedNote { port, note } => {
+                if buffer.len() < 3 {
+                    return Err(SerializationError::buffer_too_small(3, buffer.len()));
+                }
+                buffer[0] = self.opcode();
+                buffer[1] = port;
+                buffer[2] = note;
+                Ok(3)
+            }
+            Self::SetCvScale { port, scale } => {
+                if buffer.len() < 3 {
+                    return Err(SerializationError::buffer_too_small(3, buffer.len()));
+                }
+                buffer[0] = self.opcode();
+                buffer[1] = port;
+                buffer[2] = scale;
+                Ok(3)
             }
             Self::SetOscPitch { port, pitch } => {
-                let pitch_bytes = pitch.to_be_bytes();
-                buffer[0] = 0x41;
+                if buffer.len() < 4 {
+                    return Err(SerializationError::buffer_too_small(4, buffer.len()));
+                }
+                buffer[0] = self.opcode();
                 buffer[1] = port;
-                buffer[2] = pitch_bytes[0];
-                buffer[3] = pitch_bytes[1];
-                Ok(&buffer[..4])
+                put_i16_be(buffer, 2, pitch);
+                Ok(4)
+            }
+            Self::SetOscNote { port, note } => {
+                if buffer.len() < 3 {
+                    return Err(SerializationError::buffer_too_small(3, buffer.len()));
+                }
+                buffer[0] = self.opcode();
+                buffer[1] = port;
+                buffer[2] = note;
+                Ok(3)
+            }
+            Self::OscSync { port } => {
+                if buffer.len() < 2 {
+                    return Err(SerializationError::buffer_too_small(2, buffer.len()));
+                }
+                buffer[0] = self.opcode();
+                buffer[1] = port;
+                Ok(2)
+            }
+            Self::SetOscPhase { port, phase } => {
+                if buffer.len() < 4 {
+                    return Err(SerializationError::buffer_too_small(4, buffer.len()));
+                }
+                buffer[0] = self.opcode();
+                buffer[1] = port;
+                put_u16_be(buffer, 2, phase);
+                Ok(4)
+            }
+            Self::SetOscLfoMode { port, enabled } => {
+                if buffer.len() < 3 {
+                    return Err(SerializationError::buffer_too_small(3, buffer.len()));
+                }
+                buffer[0] = self.opcode();
+                buffer[1] = port;
+                buffer[2] = enabled as u8;
+                Ok(3)
+            }
+            Self::SetOscLfoRate { port, rate } => {
+                if buffer.len() < 4 {
+                    return Err(SerializationError::buffer_too_small(4, buffer.len()));
+                }
+                buffer[0] = self.opcode();
+                buffer[1] = port;
+                put_i16_be(buffer, 2, rate);
+                Ok(4)
+            }
+            Self::SetOscFreq { port, freq } => {
+                if buffer.len() < 4 {
+                    return Err(SerializationError::buffer_too_small(4, buffer.len()));
+                }
+                buffer[0] = self.opcode();
+                buffer[1] = port;
+                put_u16_be(buffer, 2, freq);
+                Ok(4)
+            }
+            Self::SetOscSlew { port, ms } => {
+                if buffer.len() < 4 {
+                    return Err(SerializationError::buffer_too_small(4, buffer.len()));
+                }
+                buffer[0] = self.opcode();
+                buffer[1] = port;
+                put_u16_be(buffer, 2, ms);
+                Ok(4)
             }
             Self::SetOscWaveform { port, waveform } => {
-                let wf_bytes = waveform.to_be_bytes();
-                buffer[0] = 0x4A;
+                if buffer.len() < 4 {
+                    return Err(SerializationError::buffer_too_small(4, buffer.len()));
+                }
+                buffer[0] = self.opcode();
+                buffer[1] = port;
+                put_u16_be(buffer, 2, waveform);
+                Ok(4)
+            }
+            Self::SetOscWidth { port, width } => {
+                if buffer.len() < 4 {
+                    return Err(SerializationError::buffer_too_small(4, buffer.len()));
+                }
+                buffer[0] = self.opcode();
+                buffer[1] = port;
+                put_u16_be(buffer, 2, width);
+                Ok(4)
+            }
+            Self::SetOscRectify { port, mode } => {
+                if buffer.len() < 3 {
+                    return Err(SerializationError::buffer_too_small(3, buffer.len()));
+                }
+                buffer[0] = self.opcode();
                 buffer[1] = port;
-                buffer[2] = wf_bytes[0];
-                buffer[3] = wf_bytes[1];
-                Ok(&buffer[..4])
+                buffer[2] = mode as u8;
+                Ok(3)
+            }
+            Self::SetOscCenter { port, value } => {
+                if buffer.len() < 4 {
+                    return Err(SerializationError::buffer_too_small(4, buffer.len()));
+                }
+                buffer[0] = self.opcode();
+                buffer[1] = port;
+                put_i16_be(buffer, 2, value);
+                Ok(4)
             }
             Self::SetEnvelopeMode { port, enabled } => {
-                buffer[0] = 0x60;
+                if buffer.len() < 3 {
+                    return Err(SerializationError::buffer_too_small(3, buffer.len()));
+                }
+                buffer[0] = self.opcode();
                 buffer[1] = port;
                 buffer[2] = enabled as u8;
-                Ok(&buffer[..3])
+                Ok(3)
+            }
+            Self::SetEnvelopeAttack { port, ms } => {
+                if buffer.len() < 4 {
+                    return Err(SerializationError::buffer_too_small(4, buffer.len()));
+                }
+                buffer[0] = self.opcode();
+                buffer[1] = port;
+                put_u16_be(buffer, 2, ms);
+                Ok(4)
+            }
+            Self::SetEnvelopeDecay { port, ms } => {
+                if buffer.len() < 4 {
+                    return Err(SerializationError::buffer_too_small(4, buffer.len()));
+                }
+                buffer[0] = self.opcode();
+                buffer[1] = port;
+                put_u16_be(buffer, 2, ms);
+                Ok(4)
+            }
+            Self::SetEnvelopeEor { port, dest } => {
+                if buffer.len() < 3 {
+                    return Err(SerializationError::buffer_too_small(3, buffer.len()));
+                }
+                buffer[0] = self.opcode();
+                buffer[1] = port;
+                buffer[2] = dest;
+                Ok(3)
+            }
+            Self::SetEnvelopeEoc { port, dest } => {
+                if buffer.len() < 3 {
+                    return Err(SerializationError::buffer_too_small(3, buffer.len()));
+                }
+                buffer[0] = self.opcode();
+                buffer[1] = port;
+                buffer[2] = dest;
+                Ok(3)
             }
             Self::SetEnvelopeState { port, on } => {
-                buffer[0] = 0x6D;
+                if buffer.len() < 3 {
+                    return Err(SerializationError::buffer_too_small(3, buffer.len()));
+                }
+                buffer[0] = self.opcode();
                 buffer[1] = port;
                 buffer[2] = on as u8;
-                Ok(&buffer[..3])
+                Ok(3)
+            }
+            Self::SetMetroTime { ms } => {
+                if buffer.len() < 3 {
+                    return Err(SerializationError::buffer_too_small(3, buffer.len()));
+                }
+                buffer[0] = self.opcode();
+                put_u16_be(buffer, 1, ms);
+                Ok(3)
+            }
+            Self::SetMetroActive { state } => {
+                if buffer.len() < 2 {
+                    return Err(SerializationError::buffer_too_small(2, buffer.len()));
+                }
+                buffer[0] = self.opcode();
+                buffer[1] = state as u8;
+                Ok(2)
+            }
+            Self::SetMetroBpm { bpm } => {
+                if buffer.len() < 3 {
+                    return Err(SerializationError::buffer_too_small(3, buffer.len()));
+                }
+                buffer[0] = self.opcode();
+                put_u16_be(buffer, 1, bpm);
+                Ok(3)
+            }
+            Self::Kill | Self::Reset => {
+                if buffer.is_empty() {
+                    return Err(SerializationError::buffer_too_small(1, 0));
+                }
+                buffer[0] = self.opcode();
+                Ok(1)
+            }
+        }
+    }
+
+    /// Checks the fields against the ranges documented on each variant.
+    fn validate(&self) -> Result<(), SerializationError> {
+        match *self {
+            Self::SetGate { port, .. }
+            | Self::SetTrToggle { port }
+            | Self::SetTrPulse { port }
+            | Self::SetTrTime { port, .. }
+            | Self::SetTrPolarity { port, .. }
+            | Self::SetCv { port, .. }
+            | Self::SetCvImmediate { port, .. }
+            | Self::SetCvSlew { port, .. }
+            | Self::SetCvOffset { port, .. }
+            | Self::SetCvQuantizedNote { port, .. }
+            | Self::SetOscPitch { port, .. }
+            | Self::OscSync { port }
+            | Self::SetOscPhase { port, .. }
+            | Self::SetOscLfoMode { port, .. }
+            | Self::SetOscLfoRate { port, .. }
+            | Self::SetOscFreq { port, .. }
+            | Self::SetOscSlew { port, .. }
+            | Self::SetOscCenter { port, .. }
+            | Self::SetEnvelopeMode { port, .. }
+            | Self::SetEnvelopeAttack { port, .. }
+            | Self::SetEnvelopeDecay { port, .. }
+            | Self::SetEnvelopeEor { port, .. }
+            | Self::SetEnvelopeEoc { port, .. }
+            | Self::SetEnvelopeState { port, .. } => check_range("port", port, 0..=3),
+            Self::SetOscNote { port, note } => {
+                check_range("port", port, 0..=3)?;
+                check_range("note", note, 0..=127)
+            }
+            Self::SetOscWidth { port, width } => {
+                check_range("port", port, 0..=3)?;
+                check_range("width", width, 0..=100)
+            }
+            Self::SetOscRectify { port, mode } => {
+                check_range("port", port, 0..=3)?;
+                check_range("mode", mode, -3..=3)
+            }
+            Self::SetOscWaveform { port, waveform } => {
+                check_range("port", port, 0..=3)?;
+                check_range("waveform", waveform, 0..=5000)
+            }
+            Self::SetCvScale { port, scale } => {
+                check_range("port", port, 0..=3)?;
+                check_range("scale", scale, 0..=15)
+            }
+            Self::SetMetroTime { .. }
+            | Self::SetMetroActive { .. }
+            | Self::SetMetroBpm { .. }
+            | Self::Kill
+            | Self::Reset => Ok(()),
+        }
+    }
+}
+
+/// Formats the command as a Teletype-style mnemonic followed by its fields in declaration
+/// order, separated by spaces. Booleans are written as 0 or 1.
+///
+/// The mnemonics are stable, so tools may parse them from logs:
+///
+/// | Command | Mnemonic |
+/// |---------|----------|
+/// | `SetGate` | `TO.TR` |
+/// | `SetTrToggle` | `TO.TR.TOG` |
+/// | `SetTrPulse` | `TO.TR.PULSE` |
+/// | `SetTrTime` | `TO.TR.TIME` |
+/// | `SetTrPolarity` | `TO.TR.POL` |
+/// | `SetCv` | `TO.CV` |
+/// | `SetCvImmediate` | `TO.CV.SET` |
+/// | `SetCvSlew` | `TO.CV.SLEW` |
+/// | `SetCvOffset` | `TO.CV.OFF` |
+/// | `SetCvQuantizedNote` | `TO.CV.QT` |
+/// | `SetCvScale` | `TO.CV.SCALE` |
+/// | `SetOscPitch` | `TO.OSC` |
+/// | `SetOscNote` | `TO.OSC.N` |
+/// | `OscSync` | `TO.OSC.SYNC` |
+/// | `SetOscPhase` | `TO.OSC.PHASE` |
+/// | `SetOscLfoMode` | `TO.OSC.LFO` |
+/// | `SetOscLfoRate` | `TO.OSC.LFO.RATE` |
+/// | `SetOscFreq` | `TO.OSC.FQ` |
+/// | `SetOscSlew` | `TO.OSC.SLEW` |
+/// | `SetOscWaveform` | `TO.OSC.WAVE` |
+/// | `SetOscWidth` | `TO.OSC.WIDTH` |
+/// | `SetOscRectify` | `TO.OSC.RECT` |
+/// | `SetOscCenter` | `TO.OSC.CTR` |
+/// | `SetEnvelopeMode` | `TO.ENV.ACT` |
+/// | `SetEnvelopeAttack` | `TO.ENV.ATT` |
+/// | `SetEnvelopeDecay` | `TO.ENV.DEC` |
+/// | `SetEnvelopeEor` | `TO.ENV.EOR` |
+/// | `SetEnvelopeEoc` | `TO.ENV.EOC` |
+/// | `SetEnvelopeState` | `TO.ENV` |
+/// | `SetMetroTime` | `TO.M` |
+/// | `SetMetroActive` | `TO.M.ACT` |
+/// | `SetMetroBpm` | `TO.M.BPM` |
+/// | `Kill` | `TO.KILL` |
+/// | `Reset` | `TO.INIT` |
+impl fmt::Display for Commands {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::SetGate { port, state } => write!(f, "TO.TR {port} {}", u8::from(state)),
+            Self::SetTrToggle { port } => write!(f, "TO.TR.TOG {port}"),
+            Self::SetTrPulse { port } => write!(f, "TO.TR.PULSE {port}"),
+            Self::SetTrTime { port, ms } => write!(f, "TO.TR.TIME {port} {ms}"),
+            Self::SetTrPolarity { port, polarity } => {
+                write!(f, "TO.TR.POL {port} {}", u8::from(polarity))
+            }
+            Self::SetCv { port, value } => write!(f, "TO.CV {port} {value}"),
+            Self::SetCvImmediate { port, value } => write!(f, "TO.CV.SET {port} {value}"),
+            Self::SetCvSlew { port, ms } => write!(f, "TO.CV.SLEW {port} {ms}"),
+            Self::SetCvOffset { port, value } => write!(f, "TO.CV.OFF {port} {value}"),
+            Self::SetCvQuantizedNote { port, note } => write!(f, "TO.CV.QT {port} {note}"),
+            Self::SetCvScale { port, scale } => write!(f, "TO.CV.SCALE {port} {scale}"),
+            Self::SetOscPitch { port, pitch } => write!(f, "TO.OSC {port} {pitch}"),
+            Self::SetOscNote { port, note } => write!(f, "TO.OSC.N {port} {note}"),
+            Self::OscSync { port } => write!(f, "TO.OSC.SYNC {port}"),
+            Self::SetOscPhase { port, phase } => write!(f, "TO.OSC.PHASE {port} {phase}"),
+            Self::SetOscLfoMode { port, enabled } => {
+                write!(f, "TO.OSC.LFO {port} {}", u8::from(enabled))
+            }
+            Self::SetOscLfoRate { port, rate } => write!(f, "TO.OSC.LFO.RATE {port} {rate}"),
+            Self::SetOscFreq { port, freq } => write!(f, "TO.OSC.FQ {port} {freq}"),
+            Self::SetOscSlew { port, ms } => write!(f, "TO.OSC.SLEW {port} {ms}"),
+            Self::SetOscWaveform { port, waveform } => write!(f, "TO.OSC.WAVE {port} {waveform}"),
+            Self::SetOscWidth { port, width } => write!(f, "TO.OSC.WIDTH {port} {width}"),
+            Self::SetOscRectify { port, mode } => write!(f, "TO.OSC.RECT {port} {mode}"),
+            Self::SetOscCenter { port, value } => write!(f, "TO.OSC.CTR {port} {value}"),
+            Self::SetEnvelopeMode { port, enabled } => {
+                write!(f, "TO.ENV.ACT {port} {}", u8::from(enabled))
+            }
+            Self::SetEnvelopeAttack { port, ms } => write!(f, "TO.ENV.ATT {port} {ms}"),
+            Self::SetEnvelopeDecay { port, ms } => write!(f, "TO.ENV.DEC {port} {ms}"),
+            Self::SetEnvelopeEor { port, dest } => write!(f, "TO.ENV.EOR {port} {dest}"),
+            Self::SetEnvelopeEoc { port, dest } => write!(f, "TO.ENV.EOC {port} {dest}"),
+            Self::SetEnvelopeState { port, on } => write!(f, "TO.ENV {port} {}", u8::from(on)),
+            Self::SetMetroTime { ms } => write!(f, "TO.M {ms}"),
+            Self::SetMetroActive { state } => write!(f, "TO.M.ACT {}", u8::from(state)),
+            Self::SetMetroBpm { bpm } => write!(f, "TO.M.BPM {bpm}"),
+            Self::Kill => f.write_str("TO.KILL"),
+            Self::Reset => f.write_str("TO.INIT"),
+        }
+    }
+}
+
+/// Formats the command as its Teletype `TO.*` op, which counts ports from 1. Ports are
+/// numbered as on the first TXo.
+impl fmt::Display for TeletypeOp<Commands> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Commands::SetGate { port, state } => {
+                write!(f, "TO.TR {} {}", u16::from(port) + 1, u8::from(state))
+            }
+            Commands::SetTrToggle { port } => write!(f, "TO.TR.TOG {}", u16::from(port) + 1),
+            Commands::SetTrPulse { port } => write!(f, "TO.TR.PULSE {}", u16::from(port) + 1),
+            Commands::SetTrTime { port, ms } => {
+                write!(f, "TO.TR.TIME {} {ms}", u16::from(port) + 1)
+            }
+            Commands::SetTrPolarity { port, polarity } => write!(
+                f,
+                "TO.TR.POL {} {}",
+                u16::from(port) + 1,
+                u8::from(polarity)
+            ),
+            Commands::SetCv { port, value } => write!(f, "TO.CV {} {value}", u16::from(port) + 1),
+            Commands::SetCvImmediate { port, value } => {
+                write!(f, "TO.CV.SET {} {value}", u16::from(port) + 1)
+            }
+            Commands::SetCvSlew { port, ms } => {
+                write!(f, "TO.CV.SLEW {} {ms}", u16::from(port) + 1)
+            }
+            Commands::SetCvOffset { port, value } => {
+                write!(f, "TO.CV.OFF {} {value}", u16::from(port) + 1)
+            }
+            Commands::SetCvQuantizedNote { port, note } => {
+                write!(f, "TO.CV.QT {} {note}", u16::from(port) + 1)
+            }
+            Commands::SetCvScale { port, scale } => {
+                write!(f, "TO.CV.SCALE {} {scale}", u16::from(port) + 1)
+            }
+            Commands::SetOscPitch { port, pitch } => {
+                write!(f, "TO.OSC {} {pitch}", u16::from(port) + 1)
+            }
+            Commands::SetOscNote { port, note } => {
+                write!(f, "TO.OSC.N {} {note}", u16::from(port) + 1)
+            }
+            Commands::OscSync { port } => write!(f, "TO.OSC.SYNC {}", u16::from(port) + 1),
+            Commands::SetOscPhase { port, phase } => {
+                write!(f, "TO.OSC.PHASE {} {phase}", u16::from(port) + 1)
+            }
+            Commands::SetOscLfoMode { port, enabled } => write!(
+                f,
+                "TO.OSC.LFO {} {}",
+                u16::from(port) + 1,
+                u8::from(enabled)
+            ),
+            Commands::SetOscLfoRate { port, rate } => {
+                write!(f, "TO.OSC.LFO.RATE {} {rate}", u16::from(port) + 1)
+            }
+            Commands::SetOscFreq { port, freq } => {
+                write!(f, "TO.OSC.FQ {} {freq}", u16::from(port) + 1)
+            }
+            Commands::SetOscSlew { port, ms } => {
+                write!(f, "TO.OSC.SLEW {} {ms}", u16::from(port) + 1)
+            }
+            Commands::SetOscWaveform { port, waveform } => {
+                write!(f, "TO.OSC.WAVE {} {waveform}", u16::from(port) + 1)
             }
+            Commands::SetOscWidth { port, width } => {
+                write!(f, "TO.OSC.WIDTH {} {width}", u16::from(port) + 1)
+            }
+            Commands::SetOscRectify { port, mode } => {
+                write!(f, "TO.OSC.RECT {} {mode}", u16::from(port) + 1)
+            }
+            Commands::SetOscCenter { port, value } => {
+                write!(f, "TO.OSC.CTR {} {value}", u16::from(port) + 1)
+            }
+            Commands::SetEnvelopeMode { port, enabled } => write!(
+                f,
+                "TO.ENV.ACT {} {}",
+                u16::from(port) + 1,
+                u8::from(enabled)
+            ),
+            Commands::SetEnvelopeAttack { port, ms } => {
+                write!(f, "TO.ENV.ATT {} {ms}", u16::from(port) + 1)
+            }
+            Commands::SetEnvelopeDecay { port, ms } => {
+                write!(f, "TO.ENV.DEC {} {ms}", u16::from(port) + 1)
+            }
+            Commands::SetEnvelopeEor { port, dest } => {
+                write!(f, "TO.ENV.EOR {} {dest}", u16::from(port) + 1)
+            }
+            Commands::SetEnvelopeEoc { port, dest } => {
+                write!(f, "TO.ENV.EOC {} {dest}", u16::from(port) + 1)
+            }
+            Commands::SetEnvelopeState { port, on } => {
+                write!(f, "TO.ENV {} {}", u16::from(port) + 1, u8::from(on))
+            }
+            // The metronome commands, `Kill` and `Reset` have no op, so they are never wrapped.
+            command => fmt::Display::fmt(&command, f),
         }
     }
 }
+
+impl Command for Commands {
+    const MAX_LENGTH: usize = 4; // All listed commands are 2 to 4 bytes long.
+
+    fn to_bytes<'a>(&self, buffer: &'a mut [u8]) -> Result<&'a [u8], SerializationError> {
+        #[cfg(feature = "strict")]
+        self.validate()?;
+        #[cfg(not(feature = "strict"))]
+        debug_assert!(self.validate().is_ok(), "field out of its documented range");
+
+        let len = self.encode(buffer)?;
+        Ok(&buffer[..len])
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            Self::Kill | Self::Reset => 1,
+            Self::SetTrToggle { .. }
+            | Self::SetTrPulse { .. }
+            | Self::SetMetroActive { .. }
+            | Self::OscSync { .. } => 2,
+            Self::SetGate { .. }
+            | Self::SetTrPolarity { .. }
+            | Self::SetOscNote { .. }
+            | Self::SetOscRectify { .. }
+            | Self::SetEnvelopeMode { .. }
+            | Self::SetEnvelopeState { .. }
+            | Self::SetMetroTime { .. }
+            | Self::SetMetroBpm { .. }
+            | Self::SetCvQuantizedNote { .. }
+            | Self::SetOscLfoMode { .. }
+            | Self::SetEnvelopeEor { .. }
+            | Self::SetEnvelopeEoc { .. }
+            | Self::SetCvScale { .. } => 3,
+            Self::SetTrTime { .. }
+            | Self::SetCv { .. }
+            | Self::SetCvSlew { .. }
+            | Self::SetOscPitch { .. }
+            | Self::SetOscFreq { .. }
+            | Self::SetOscWaveform { .. }
+            | Self::SetOscWidth { .. }
+            | Self::SetEnvelopeAttack { .. }
+            | Self::SetEnvelopeDecay { .. }
+            | Self::SetCvOffset { .. }
+            | Self::SetOscPhase { .. }
+            | Self::SetOscLfoRate { .. }
+            | Self::SetOscSlew { .. }
+            | Self::SetCvImmediate { .. }
+            | Self::SetOscCenter { .. } => 4,
+        }
+    }
+
+    fn address(&self, index: u8) -> Option<u8> {
+        address(index)
+    }
+}
+
+impl Parse for Commands {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, usize), DeserializationError> {
+        let (&opcode, payload) = bytes
+            .split_first()
+            .ok_or(DeserializationError::UnexpectedEof)?;
+        let command = match (opcode, payload) {
+            (0x00, &[port, state, ..]) => Self::SetGate {
+                port,
+                state: parse_bool(state)?,
+            },
+            (0x13, &[port, ..]) => Self::SetTrToggle { port },
+            (0x14, &[port, ..]) => Self::SetTrPulse { port },
+            (0x15, &[port, hi, lo, ..]) => Self::SetTrTime {
+                port,
+                ms: u16::from_be_bytes([hi, lo]),
+            },
+            (0x16, &[port, polarity, ..]) => Self::SetTrPolarity {
+                port,
+                polarity: parse_bool(polarity)?,
+            },
+            (0x11, &[port, hi, lo, ..]) => Self::SetCv {
+                port,
+                value: i16::from_be_bytes([hi, lo]),
+            },
+            (0x10, &[port, hi, lo, ..]) => Self::SetCvImmediate {
+                port,
+                value: i16::from_be_bytes([hi, lo]),
+            },
+            (0x12, &[port, hi, lo, ..]) => Self::SetCvSlew {
+                port,
+                ms: u16::from_be_bytes([hi, lo]),
+            },
+            (0x17, &[port, hi, lo, ..]) => Self::SetCvOffset {
+                port,
+                value: i16::from_be_bytes([hi, lo]),
+            },
+            (0x18, &[port, note, ..]) => Self::SetCvQuantizedNote { port, note },
+            (0x19, &[port, scale, ..]) => Self::SetCvScale { port, scale },
+            (0x41, &[port, hi, lo, ..]) => Self::SetOscPitch {
+                port,
+                pitch: i16::from_be_bytes([hi, lo]),
+            },
+            (0x42, &[port, note, ..]) => Self::SetOscNote { port, note },
+            (0x43, &[port, ..]) => Self::OscSync { port },
+            (0x44, &[port, hi, lo, ..]) => Self::SetOscPhase {
+                port,
+                phase: u16::from_be_bytes([hi, lo]),
+            },
+            (0x45, &[port, enabled, ..]) => Self::SetOscLfoMode {
+                port,
+                enabled: parse_bool(enabled)?,
+            },
+            (0x46, &[port, hi, lo, ..]) => Self::SetOscLfoRate {
+                port,
+                rate: i16::from_be_bytes([hi, lo]),
+            },
+            (0x48, &[port, hi, lo, ..]) => Self::SetOscFreq {
+                port,
+                freq: u16::from_be_bytes([hi, lo]),
+            },
+            (0x49, &[port, hi, lo, ..]) => Self::SetOscSlew {
+                port,
+                ms: u16::from_be_bytes([hi, lo]),
+            },
+            (0x4A, &[port, hi, lo, ..]) => Self::SetOscWaveform {
+                port,
+                waveform: u16::from_be_bytes([hi, lo]),
+            },
+            (0x4B, &[port, hi, lo, ..]) => Self::SetOscWidth {
+                port,
+                width: u16::from_be_bytes([hi, lo]),
+            },
+            (0x4C, &[port, mode, ..]) => Self::SetOscRectify {
+                port,
+                mode: mode as i8,
+            },
+            (0x4D, &[port, hi, lo, ..]) => Self::SetOscCenter {
+                port,
+                value: i16::from_be_bytes([hi, lo]),
+            },
+            (0x60, &[port, enabled, ..]) => Self::SetEnvelopeMode {
+                port,
+                enabled: parse_bool(enabled)?,
+            },
+            (0x61, &[port, hi, lo, ..]) => Self::SetEnvelopeAttack {
+                port,
+                ms: u16::from_be_bytes([hi, lo]),
+            },
+            (0x62, &[port, hi, lo, ..]) => Self::SetEnvelopeDecay {
+                port,
+                ms: u16::from_be_bytes([hi, lo]),
+            },
+            (0x63, &[port, dest, ..]) => Self::SetEnvelopeEor { port, dest },
+            (0x64, &[port, dest, ..]) => Self::SetEnvelopeEoc { port, dest },
+            (0x6D, &[port, on, ..]) => Self::SetEnvelopeState {
+                port,
+                on: parse_bool(on)?,
+            },
+            (0x30, &[hi, lo, ..]) => Self::SetMetroTime {
+                ms: u16::from_be_bytes([hi, lo]),
+            },
+            (0x31, &[state, ..]) => Self::SetMetroActive {
+                state: parse_bool(state)?,
+            },
+            (0x32, &[hi, lo, ..]) => Self::SetMetroBpm {
+                bpm: u16::from_be_bytes([hi, lo]),
+            },
+            (0x70, _) => Self::Kill,
+            (0x71, _) => Self::Reset,
+            (
+                0x00 | 0x10..=0x19 | 0x30..=0x32 | 0x41..=0x46 | 0x48..=0x4D | 0x60..=0x64 | 0x6D,
+                _,
+            ) => return Err(DeserializationError::UnexpectedEof),
+            (opcode, _) => return Err(DeserializationError::UnknownOpcode(opcode)),
+        };
+        Ok((command, command.len()))
+    }
+}
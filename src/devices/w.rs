@@ -0,0 +1,354 @@
+//! Commands for the Mannequins W/ in tape mode.
+//!
+//! W/ changed its II command map substantially between firmware 1.x and 2.x. This
+//! module follows the 2.x map (`W/.*` ops); units still running 1.x firmware will not
+//! respond to these opcodes correctly. This includes the loop point and cue commands
+//! (`0x05`-`0x07`) and the record arm, threshold and erase commands (`0x08`-`0x0A`).
+//!
+//! `Erase` wipes the recorded tape and cannot be undone, so hosts should confirm it with
+//! the user rather than send it from an automated sequence.
+
+use core::fmt;
+
+use crate::{
+    Command, CommandCategory, DeserializationError, DeviceDescriptor, Parse, SerializationError,
+    TargetKey, TeletypeOp, parse_bool, put_i16_be,
+};
+
+/// The fixed I2C address for W/ in tape mode.
+pub const ADDRESS: u8 = 0x71;
+
+/// Resolves the I2C address of the device with the given index.
+/// The address is fixed, so only index 0 is valid and any other index returns `None`.
+pub const fn address(index: u8) -> Option<u8> {
+    match index {
+        0 => Some(ADDRESS),
+        _ => None,
+    }
+}
+
+/// Metadata describing the W/.
+pub const DESCRIPTOR: DeviceDescriptor = DeviceDescriptor {
+    name: "W/",
+    default_address: ADDRESS,
+    address_range: ADDRESS..=ADDRESS,
+    max_command_length: <Commands as Command>::MAX_LENGTH,
+    has_cv: false,
+    has_gates: false,
+    has_osc: false,
+    has_envelope: false,
+    cv_channels: 0,
+    gate_channels: 0,
+};
+
+/// All supported II commands for W/.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Commands {
+    /// `W/.PLAY`: Starts playback at the given speed.
+    /// - `speed`: A signed 16-bit speed value. Negative values play in reverse.
+    Play { speed: i16 },
+    /// `W/.REC`: Enables or disables recording.
+    /// - `state`: `true` to record, `false` to stop recording.
+    Record { state: bool },
+    /// `W/.LOOP`: Sets the loop points and activates looping.
+    /// - `start`: A signed 16-bit loop start position.
+    /// - `end`: A signed 16-bit loop end position.
+    Loop { start: i16, end: i16 },
+    /// `W/.SPEED`: Changes the playback speed without affecting the transport.
+    /// - `value`: A signed 16-bit speed value. Negative values play in reverse.
+    SetSpeed { value: i16 },
+    /// `W/.LOOP.START`: Moves the start point of the loop.
+    /// - `position`: A signed 16-bit tape position.
+    SetLoopStart { position: i16 },
+    /// `W/.LOOP.END`: Moves the end point of the loop.
+    /// - `position`: A signed 16-bit tape position.
+    SetLoopEnd { position: i16 },
+    /// `W/.CUE`: Moves the play head to a tape position.
+    /// - `position`: A signed 16-bit tape position, in the same units as the loop points.
+    CueTo { position: i16 },
+    /// `W/.REC.ARM`: Arms recording, so it starts once the input crosses the record
+    /// threshold.
+    /// - `state`: `true` to arm, `false` to disarm.
+    RecordArm { state: bool },
+    /// `W/.REC.THRESH`: Sets the input level at which an armed recording starts.
+    /// - `value`: A signed 16-bit level, scaled like a CV.
+    SetRecordThreshold { value: i16 },
+    /// `W/.CLEARTAPE`: Erases the whole tape.
+    ///
+    /// This is irreversible: the recorded audio is gone once W/ receives the message, and
+    /// no II command restores it.
+    Erase,
+}
+
+impl Commands {
+    /// Returns the opcode this command writes as its first byte.
+    pub const fn opcode(&self) -> u8 {
+        match self {
+            Self::Play { .. } => 0x01,
+            Self::Record { .. } => 0x02,
+            Self::Loop { .. } => 0x03,
+            Self::SetSpeed { .. } => 0x04,
+            Self::SetLoopStart { .. } => 0x05,
+            Self::SetLoopEnd { .. } => 0x06,
+            Self::CueTo { .. } => 0x07,
+            Self::RecordArm { .. } => 0x08,
+            Self::SetRecordThreshold { .. } => 0x09,
+            Self::Erase => 0x0A,
+        }
+    }
+
+    /// Returns the category of the command, e.g. for prioritizing II traffic.
+    pub const fn category(&self) -> CommandCategory {
+        match self {
+            Self::Play { .. }
+            | Self::Record { .. }
+            | Self::Loop { .. }
+            | Self::SetSpeed { .. }
+            | Self::SetLoopStart { .. }
+            | Self::SetLoopEnd { .. }
+            | Self::CueTo { .. }
+            | Self::RecordArm { .. }
+            | Self::SetRecordThreshold { .. }
+            | Self::Erase => CommandCategory::Transport,
+        }
+    }
+
+    /// Returns the equivalent Teletype `W/.*` op.
+    ///
+    /// Returns `None` for `RecordArm` and `SetRecordThreshold`, which Teletype has no ops
+    /// for.
+    pub const fn teletype_op(&self) -> Option<TeletypeOp<Self>> {
+        match self {
+            Self::RecordArm { .. } | Self::SetRecordThreshold { .. } => None,
+            _ => Some(TeletypeOp(*self)),
+        }
+    }
+
+    /// Returns the key identifying the destination the command writes to, see
+    /// [`TargetKey`].
+    ///
+    /// Returns `None` for `CueTo` and `Erase`, which act on every send.
+    pub const fn target_key(&self) -> Option<TargetKey> {
+        match *self {
+            Self::CueTo { .. } | Self::Erase => None,
+            Self::Play { .. }
+            | Self::Record { .. }
+            | Self::RecordArm { .. }
+            | Self::SetRecordThreshold { .. }
+            | Self::Loop { .. }
+            | Self::SetSpeed { .. }
+            | Self::SetLoopStart { .. }
+            | Self::SetLoopEnd { .. } => Some(TargetKey::new(self.opcode(), 0)),
+        }
+    }
+
+    /// Serializes the command into a fixed-size array, usable in `const` context.
+    ///
+    /// Returns the array and the number of valid bytes at its start. Unlike
+    /// [`Command::to_bytes`], this does not check field ranges.
+    pub const fn to_bytes_array(&self) -> ([u8; <Self as Command>::MAX_LENGTH], usize) {
+        let mut buffer = [0u8; <Self as Command>::MAX_LENGTH];
+        match self.encode(&mut buffer) {
+            Ok(len) => (buffer, len),
+            Err(_) => panic!("MAX_LENGTH is shorter than the command"),
+        }
+    }
+
+    /// Writes the command to the start of `buffer` and returns the number of bytes written.
+    const fn encode(&self, buffer: &mut [u8]) -> Result<usize, SerializationError> {
+        match *self {
+            Self::Play { speed } => {
+                if buffer.len() < 3 {
+                    return Err(SerializationError::buffer_too_small(3, buffer.len()));
+                }
+                buffer[0] = self.opcode();
+                put_i16_be(buffer, 1, speed);
+                Ok(3)
+            }
+            Self::Record { state } => {
+                if buffer.len() < 2 {
+                    return Err(SerializationError::buffer_too_small(2, buffer.len()));
+                }
+                buffer[0] = self.opcode();
+                buffer[1] = state as u8;
+                Ok(2)
+            }
+            Self::Loop { start, end } => {
+                if buffer.len() < 5 {
+                    return Err(SerializationError::buffer_too_small(5, buffer.len()));
+                }
+                buffer[0] = self.opcode();
+                put_i16_be(buffer, 1, start);
+                put_i16_be(buffer, 3, end);
+                Ok(5)
+            }
+            Self::SetSpeed { value } => {
+                if buffer.len() < 3 {
+                    return Err(SerializationError::buffer_too_small(3, buffer.len()));
+                }
+                buffer[0] = self.opcode();
+                put_i16_be(buffer, 1, value);
+                Ok(3)
+            }
+            Self::SetLoopStart { position } => {
+                if buffer.len() < 3 {
+                    return Err(SerializationError::buffer_too_small(3, buffer.len()));
+                }
+                buffer[0] = self.opcode();
+                put_i16_be(buffer, 1, position);
+                Ok(3)
+            }
+            Self::SetLoopEnd { position } => {
+                if buffer.len() < 3 {
+                    return Err(SerializationError::buffer_too_small(3, buffer.len()));
+                }
+                buffer[0] = self.opcode();
+                put_i16_be(buffer, 1, position);
+                Ok(3)
+            }
+            Self::CueTo { position } => {
+                if buffer.len() < 3 {
+                    return Err(SerializationError::buffer_too_small(3, buffer.len()));
+                }
+                buffer[0] = self.opcode();
+                put_i16_be(buffer, 1, position);
+                Ok(3)
+            }
+            Self::RecordArm { state } => {
+                if buffer.len() < 2 {
+                    return Err(SerializationError::buffer_too_small(2, buffer.len()));
+                }
+                buffer[0] = self.opcode();
+                buffer[1] = state as u8;
+                Ok(2)
+            }
+            Self::SetRecordThreshold { value } => {
+                if buffer.len() < 3 {
+                    return Err(SerializationError::buffer_too_small(3, buffer.len()));
+                }
+                buffer[0] = self.opcode();
+                put_i16_be(buffer, 1, value);
+                Ok(3)
+            }
+            Self::Erase => {
+                if buffer.is_empty() {
+                    return Err(SerializationError::buffer_too_small(1, 0));
+                }
+                buffer[0] = self.opcode();
+                Ok(1)
+            }
+        }
+    }
+}
+
+/// Formats the command as a Teletype-style mnemonic followed by its fields in declaration
+/// order, separated by spaces. Booleans are written as 0 or 1.
+///
+/// The mnemonics are stable, so tools may parse them from logs:
+///
+/// | Command | Mnemonic |
+/// |---------|----------|
+/// | `Play` | `W/.PLAY` |
+/// | `Record` | `W/.REC` |
+/// | `Loop` | `W/.LOOP` |
+/// | `SetSpeed` | `W/.SPEED` |
+/// | `SetLoopStart` | `W/.LOOP.START` |
+/// | `SetLoopEnd` | `W/.LOOP.END` |
+/// | `CueTo` | `W/.CUE` |
+/// | `RecordArm` | `W/.REC.ARM` |
+/// | `SetRecordThreshold` | `W/.REC.THRESH` |
+/// | `Erase` | `W/.CLEARTAPE` |
+impl fmt::Display for Commands {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::Play { speed } => write!(f, "W/.PLAY {speed}"),
+            Self::Record { state } => write!(f, "W/.REC {}", u8::from(state)),
+            Self::Loop { start, end } => write!(f, "W/.LOOP {start} {end}"),
+            Self::SetSpeed { value } => write!(f, "W/.SPEED {value}"),
+            Self::SetLoopStart { position } => write!(f, "W/.LOOP.START {position}"),
+            Self::SetLoopEnd { position } => write!(f, "W/.LOOP.END {position}"),
+            Self::CueTo { position } => write!(f, "W/.CUE {position}"),
+            Self::RecordArm { state } => write!(f, "W/.REC.ARM {}", u8::from(state)),
+            Self::SetRecordThreshold { value } => write!(f, "W/.REC.THRESH {value}"),
+            Self::Erase => f.write_str("W/.CLEARTAPE"),
+        }
+    }
+}
+
+/// Formats the command as its Teletype `W/.*` op, which matches its `Display` output.
+impl fmt::Display for TeletypeOp<Commands> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl Command for Commands {
+    const MAX_LENGTH: usize = 5; // Loop is the longest command.
+
+    fn to_bytes<'a>(&self, buffer: &'a mut [u8]) -> Result<&'a [u8], SerializationError> {
+        let len = self.encode(buffer)?;
+        Ok(&buffer[..len])
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            Self::Erase => 1,
+            Self::Record { .. } | Self::RecordArm { .. } => 2,
+            Self::Play { .. }
+            | Self::SetSpeed { .. }
+            | Self::SetLoopStart { .. }
+            | Self::SetLoopEnd { .. }
+            | Self::CueTo { .. }
+            | Self::SetRecordThreshold { .. } => 3,
+            Self::Loop { .. } => 5,
+        }
+    }
+
+    fn address(&self, _index: u8) -> Option<u8> {
+        Some(ADDRESS)
+    }
+}
+
+impl Parse for Commands {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, usize), DeserializationError> {
+        let (&opcode, payload) = bytes
+            .split_first()
+            .ok_or(DeserializationError::UnexpectedEof)?;
+        let command = match (opcode, payload) {
+            (0x01, &[hi, lo, ..]) => Self::Play {
+                speed: i16::from_be_bytes([hi, lo]),
+            },
+            (0x02, &[state, ..]) => Self::Record {
+                state: parse_bool(state)?,
+            },
+            (0x03, &[start_hi, start_lo, end_hi, end_lo, ..]) => Self::Loop {
+                start: i16::from_be_bytes([start_hi, start_lo]),
+                end: i16::from_be_bytes([end_hi, end_lo]),
+            },
+            (0x04, &[hi, lo, ..]) => Self::SetSpeed {
+                value: i16::from_be_bytes([hi, lo]),
+            },
+            (0x05, &[hi, lo, ..]) => Self::SetLoopStart {
+                position: i16::from_be_bytes([hi, lo]),
+            },
+            (0x06, &[hi, lo, ..]) => Self::SetLoopEnd {
+                position: i16::from_be_bytes([hi, lo]),
+            },
+            (0x07, &[hi, lo, ..]) => Self::CueTo {
+                position: i16::from_be_bytes([hi, lo]),
+            },
+            (0x08, &[state, ..]) => Self::RecordArm {
+                state: parse_bool(state)?,
+            },
+            (0x09, &[hi, lo, ..]) => Self::SetRecordThreshold {
+                value: i16::from_be_bytes([hi, lo]),
+            },
+            (0x0A, _) => Self::Erase,
+            (0x01..=0x09, _) => return Err(DeserializationError::UnexpectedEof),
+            (opcode, _) => return Err(DeserializationError::UnknownOpcode(opcode)),
+        };
+        Ok((command, command.len()))
+    }
+}
@@ -0,0 +1,518 @@
+//! Golden byte vectors for every device command.
+//!
+//! Each table lists every command of a device with representative field values and the
+//! exact bytes it must serialize to. The completeness check fails when a device gains an
+//! opcode without a matching vector, so new commands cannot be added untested.
+
+use core::fmt::Debug;
+
+use crate::devices::*;
+use crate::{Command, DeserializationError, Parse};
+
+/// Asserts that every vector serializes to its bytes and that every opcode the device
+/// parses is covered by at least one vector.
+fn check_vectors<C: Command + Parse + Debug>(vectors: &[(C, &[u8])]) {
+    let mut buffer = [0u8; 16];
+    for (command, expected) in vectors {
+        assert_eq!(command.to_bytes(&mut buffer), Ok(*expected), "{command:?}");
+    }
+
+    for opcode in 0..=u8::MAX {
+        let mut bytes = [0u8; 16];
+        bytes[0] = opcode;
+        if matches!(
+            C::from_bytes(&bytes),
+            Err(DeserializationError::UnknownOpcode(_))
+        ) {
+            continue;
+        }
+        assert!(
+            vectors.iter().any(|(_, expected)| expected[0] == opcode),
+            "opcode {opcode:#04X} has no golden vector"
+        );
+    }
+}
+
+#[test]
+fn ansible() {
+    use ansible::Commands::*;
+    check_vectors(&[
+        (
+            SetCv {
+                port: 3,
+                value: -4096,
+            },
+            &[0x01, 0x03, 0xF0, 0x00],
+        ),
+        (SetCvSlew { port: 1, ms: 500 }, &[0x02, 0x01, 0x01, 0xF4]),
+        (
+            SetCvFromFader {
+                device_port: 2,
+                value: 40000,
+            },
+            &[0x06, 0x02, 0x9C, 0x40],
+        ),
+        (
+            SetTrState {
+                port: 0,
+                state: true,
+            },
+            &[0x10, 0x00, 0x01],
+        ),
+        (SetTrToggle { port: 1 }, &[0x11, 0x01]),
+        (SetTrPulse { port: 2 }, &[0x12, 0x02]),
+        (
+            SetTrPulseDuration { port: 3, ms: 20 },
+            &[0x13, 0x03, 0x00, 0x14],
+        ),
+        (LoadPreset { preset: 7 }, &[0x20, 0x07]),
+        (SavePreset { preset: 1 }, &[0x21, 0x01]),
+        (
+            KriaSetStep {
+                track: 4,
+                step: 15,
+                state: 2,
+            },
+            &[0x30, 0x04, 0x0F, 0x02],
+        ),
+        (KriaSetTempo { bpm: 128 }, &[0x31, 0x00, 0x80]),
+        (KriaSelectPattern { pattern: 5 }, &[0x32, 0x05]),
+        (
+            KriaMute {
+                track: 2,
+                state: true,
+            },
+            &[0x33, 0x02, 0x01],
+        ),
+        (
+            KriaTranspose {
+                track: 1,
+                semitones: -7,
+            },
+            &[0x34, 0x01, 0xF9],
+        ),
+        (MeadowphysicsStart, &[0x40]),
+        (MeadowphysicsStop, &[0x41]),
+        (
+            MeadowphysicsSetSpeed { row: 6, speed: 300 },
+            &[0x42, 0x06, 0x01, 0x2C],
+        ),
+        (MeadowphysicsReset { row: 0 }, &[0x43, 0x00]),
+        (
+            SetTrPolarity {
+                port: 2,
+                polarity: false,
+            },
+            &[0x14, 0x02, 0x00],
+        ),
+        (
+            LevelsSetValue {
+                track: 3,
+                value: 4096,
+            },
+            &[0x50, 0x03, 0x10, 0x00],
+        ),
+        (LevelsReset, &[0x51]),
+        (
+            CyclesSetFreq {
+                channel: 2,
+                freq: 440,
+            },
+            &[0x60, 0x02, 0x01, 0xB8],
+        ),
+        (CyclesReset, &[0x61]),
+        (RequestCurrentPreset, &[0x22]),
+    ]);
+}
+
+#[test]
+fn crow() {
+    use crow::Commands::*;
+    check_vectors(&[
+        (
+            SetOutput {
+                channel: 1,
+                value: 8192,
+            },
+            &[0x01, 0x01, 0x20, 0x00],
+        ),
+        (
+            CallFunction { index: 4, arg: -1 },
+            &[0x02, 0x04, 0xFF, 0xFF],
+        ),
+        (Query { index: 2 }, &[0x03, 0x02]),
+    ]);
+}
+
+#[test]
+fn disting_ex() {
+    use disting_ex::Commands::*;
+    check_vectors(&[
+        (SelectAlgorithm { index: 12 }, &[0x44, 0x0C]),
+        (
+            SetParameter {
+                param: 3,
+                value: -100,
+            },
+            &[0x46, 0x03, 0xFF, 0x9C],
+        ),
+        (PlaySample { slot: 9 }, &[0x4D, 0x09]),
+        (
+            SetZModeParameter {
+                param: 1,
+                value: 1000,
+            },
+            &[0x4A, 0x01, 0x03, 0xE8],
+        ),
+        (LoadPreset { index: 12 }, &[0x40, 0x0C]),
+        (SavePreset { index: 12 }, &[0x41, 0x0C]),
+        (SelectSdFile { folder: 2, file: 7 }, &[0x4B, 0x02, 0x07]),
+        (
+            NoteOn {
+                note: 60,
+                velocity: 100,
+            },
+            &[0x55, 0x3C, 0x64],
+        ),
+        (NoteOff { note: 60 }, &[0x56, 0x3C]),
+    ]);
+}
+
+#[test]
+fn er301() {
+    use er301::Commands::*;
+    check_vectors(&[
+        (
+            SetGate {
+                port: 99,
+                state: true,
+            },
+            &[0x00, 0x63, 0x01],
+        ),
+        (SetTrToggle { port: 4 }, &[0x13, 0x04]),
+        (SetTrPulse { port: 5 }, &[0x14, 0x05]),
+        (SetTrTime { port: 6, ms: 1000 }, &[0x15, 0x06, 0x03, 0xE8]),
+        (
+            SetTrPolarity {
+                port: 7,
+                polarity: false,
+            },
+            &[0x16, 0x07, 0x00],
+        ),
+        (
+            SetCv {
+                port: 5,
+                value: 8192,
+            },
+            &[0x11, 0x05, 0x20, 0x00],
+        ),
+        (
+            SetCvImmediate {
+                port: 5,
+                value: -8192,
+            },
+            &[0x10, 0x05, 0xE0, 0x00],
+        ),
+        (SetCvSlew { port: 5, ms: 250 }, &[0x12, 0x05, 0x00, 0xFA]),
+    ]);
+}
+
+#[test]
+fn faderbank() {
+    use faderbank::Commands::*;
+    check_vectors(&[(
+        SetCvFromFader {
+            device_port: 2,
+            value: 40000,
+        },
+        &[0x06, 0x02, 0x9C, 0x40],
+    )]);
+}
+
+#[test]
+fn fh2() {
+    use fh2::Commands::*;
+    check_vectors(&[
+        (
+            SetGate {
+                channel: 40,
+                state: true,
+            },
+            &[0x00, 0x28, 0x01],
+        ),
+        (
+            SetCv {
+                channel: 63,
+                value: -16384,
+            },
+            &[0x11, 0x3F, 0xC0, 0x00],
+        ),
+        (SetClockDivision { value: 4 }, &[0x20, 0x04]),
+        (SetClockBpm { bpm: 120 }, &[0x21, 0x00, 0x78]),
+        (Start, &[0x22]),
+        (Stop, &[0x23]),
+        (Continue, &[0x24]),
+    ]);
+}
+
+#[test]
+fn just_friends() {
+    use just_friends::Commands::*;
+    check_vectors(&[
+        (
+            SetGate {
+                output: 0,
+                state: true,
+            },
+            &[0x01, 0x00, 0x01],
+        ),
+        (
+            PlayNote {
+                output: 6,
+                pitch: -1638,
+                volume: 8000,
+            },
+            &[0x08, 0x06, 0xF9, 0x9A, 0x1F, 0x40],
+        ),
+        (
+            SetVoice {
+                channel: 2,
+                pitch: 1638,
+                level: 4000,
+            },
+            &[0x09, 0x02, 0x06, 0x66, 0x0F, 0xA0],
+        ),
+        (
+            SetIntone {
+                channel: 0,
+                ratio: -2000,
+            },
+            &[0x15, 0x00, 0xF8, 0x30],
+        ),
+        (SetMode { on: true }, &[0x06, 0x01]),
+        (SetRun { value: 3000 }, &[0x03, 0x0B, 0xB8]),
+        (SetTranspose { value: -1638 }, &[0x04, 0xF9, 0x9A]),
+        (SetRamp { value: 100 }, &[0x11, 0x00, 0x64]),
+        (SetCurve { value: -100 }, &[0x12, 0xFF, 0x9C]),
+        (SetFm { value: 0 }, &[0x13, 0x00, 0x00]),
+        (SetQuantize { on: true }, &[0x0C, 0x01]),
+        (
+            Tune {
+                channel: 3,
+                value: -27,
+            },
+            &[0x0B, 0x03, 0xFF, 0xE5],
+        ),
+        (SetRunMode { mode: 1 }, &[0x02, 0x01]),
+        (SetGodMode { on: true }, &[0x0A, 0x01]),
+        (
+            SetTime {
+                channel: 2,
+                value: 4096,
+            },
+            &[0x14, 0x02, 0x10, 0x00],
+        ),
+    ]);
+}
+
+#[test]
+fn teletype() {
+    use teletype::Commands::*;
+    check_vectors(&[
+        (SetScript { script: 10 }, &[0x00, 0x0A]),
+        (SetTrigger { script: 3 }, &[0x01, 0x03]),
+        (
+            SetPattern {
+                index: 63,
+                value: -1000,
+            },
+            &[0x02, 0x3F, 0xFC, 0x18],
+        ),
+    ]);
+}
+
+#[test]
+fn telexi() {
+    use telexi::Commands::*;
+    check_vectors(&[
+        (
+            SetInputScale {
+                port: 1,
+                min: -1000,
+                max: 1000,
+            },
+            &[0x03, 0x01, 0xFC, 0x18, 0x03, 0xE8],
+        ),
+        (
+            SetInputMap {
+                port: 2,
+                top: 16383,
+                bottom: -16384,
+            },
+            &[0x04, 0x02, 0x3F, 0xFF, 0xC0, 0x00],
+        ),
+        (SetParamQuantize { port: 3, scale: 4 }, &[0x11, 0x03, 0x04]),
+        (Store, &[0x20]),
+        (Reset, &[0x21]),
+        (RequestInput { port: 2 }, &[0x00, 0x02]),
+        (RequestParam { port: 1 }, &[0x10, 0x01]),
+        (
+            SetParamScale {
+                port: 1,
+                min: -1000,
+                max: 1000,
+            },
+            &[0x13, 0x01, 0xFC, 0x18, 0x03, 0xE8],
+        ),
+        (
+            SetParamMap {
+                port: 2,
+                top: 100,
+                bottom: -100,
+            },
+            &[0x14, 0x02, 0x00, 0x64, 0xFF, 0x9C],
+        ),
+    ]);
+}
+
+#[test]
+fn telexo() {
+    use telexo::Commands::*;
+    check_vectors(&[
+        (
+            SetGate {
+                port: 0,
+                state: true,
+            },
+            &[0x00, 0x00, 0x01],
+        ),
+        (SetTrToggle { port: 1 }, &[0x13, 0x01]),
+        (SetTrPulse { port: 2 }, &[0x14, 0x02]),
+        (SetTrTime { port: 3, ms: 15 }, &[0x15, 0x03, 0x00, 0x0F]),
+        (
+            SetTrPolarity {
+                port: 0,
+                polarity: true,
+            },
+            &[0x16, 0x00, 0x01],
+        ),
+        (SetCv { port: 1, value: -1 }, &[0x11, 0x01, 0xFF, 0xFF]),
+        (SetCvSlew { port: 2, ms: 65535 }, &[0x12, 0x02, 0xFF, 0xFF]),
+        (
+            SetOscPitch {
+                port: 3,
+                pitch: 2048,
+            },
+            &[0x41, 0x03, 0x08, 0x00],
+        ),
+        (SetOscNote { port: 0, note: 60 }, &[0x42, 0x00, 0x3C]),
+        (SetOscFreq { port: 1, freq: 440 }, &[0x48, 0x01, 0x01, 0xB8]),
+        (
+            SetOscWaveform {
+                port: 2,
+                waveform: 5000,
+            },
+            &[0x4A, 0x02, 0x13, 0x88],
+        ),
+        (
+            SetOscWidth { port: 3, width: 50 },
+            &[0x4B, 0x03, 0x00, 0x32],
+        ),
+        (SetOscRectify { port: 0, mode: -3 }, &[0x4C, 0x00, 0xFD]),
+        (
+            SetEnvelopeMode {
+                port: 1,
+                enabled: true,
+            },
+            &[0x60, 0x01, 0x01],
+        ),
+        (
+            SetEnvelopeAttack { port: 2, ms: 10 },
+            &[0x61, 0x02, 0x00, 0x0A],
+        ),
+        (
+            SetEnvelopeDecay { port: 3, ms: 2000 },
+            &[0x62, 0x03, 0x07, 0xD0],
+        ),
+        (SetEnvelopeState { port: 0, on: false }, &[0x6D, 0x00, 0x00]),
+        (SetMetroTime { ms: 500 }, &[0x30, 0x01, 0xF4]),
+        (SetMetroActive { state: true }, &[0x31, 0x01]),
+        (SetMetroBpm { bpm: 120 }, &[0x32, 0x00, 0x78]),
+        (
+            SetCvOffset {
+                port: 1,
+                value: -1638,
+            },
+            &[0x17, 0x01, 0xF9, 0x9A],
+        ),
+        (
+            SetCvQuantizedNote { port: 2, note: 36 },
+            &[0x18, 0x02, 0x24],
+        ),
+        (OscSync { port: 3 }, &[0x43, 0x03]),
+        (
+            SetOscPhase {
+                port: 0,
+                phase: 32768,
+            },
+            &[0x44, 0x00, 0x80, 0x00],
+        ),
+        (
+            SetOscLfoMode {
+                port: 1,
+                enabled: true,
+            },
+            &[0x45, 0x01, 0x01],
+        ),
+        (
+            SetOscLfoRate {
+                port: 2,
+                rate: -300,
+            },
+            &[0x46, 0x02, 0xFE, 0xD4],
+        ),
+        (SetEnvelopeEor { port: 0, dest: 2 }, &[0x63, 0x00, 0x02]),
+        (SetEnvelopeEoc { port: 1, dest: 255 }, &[0x64, 0x01, 0xFF]),
+        (SetOscSlew { port: 3, ms: 250 }, &[0x49, 0x03, 0x00, 0xFA]),
+        (
+            SetCvImmediate {
+                port: 3,
+                value: -8192,
+            },
+            &[0x10, 0x03, 0xE0, 0x00],
+        ),
+        (
+            SetOscCenter {
+                port: 1,
+                value: -27,
+            },
+            &[0x4D, 0x01, 0xFF, 0xE5],
+        ),
+        (SetCvScale { port: 2, scale: 3 }, &[0x19, 0x02, 0x03]),
+        (Kill, &[0x70]),
+        (Reset, &[0x71]),
+    ]);
+}
+
+#[test]
+fn w() {
+    use w::Commands::*;
+    check_vectors(&[
+        (Play { speed: -2 }, &[0x01, 0xFF, 0xFE]),
+        (Record { state: true }, &[0x02, 0x01]),
+        (
+            Loop {
+                start: -100,
+                end: 32767,
+            },
+            &[0x03, 0xFF, 0x9C, 0x7F, 0xFF],
+        ),
+        (SetSpeed { value: 1 }, &[0x04, 0x00, 0x01]),
+        (SetLoopStart { position: -100 }, &[0x05, 0xFF, 0x9C]),
+        (SetLoopEnd { position: 2000 }, &[0x06, 0x07, 0xD0]),
+        (CueTo { position: 0 }, &[0x07, 0x00, 0x00]),
+        (RecordArm { state: true }, &[0x08, 0x01]),
+        (SetRecordThreshold { value: 1638 }, &[0x09, 0x06, 0x66]),
+        (Erase, &[0x0A]),
+    ]);
+}
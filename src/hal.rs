@@ -0,0 +1,47 @@
+//! Helpers for writing commands to an `embedded-hal` I2C bus.
+//!
+//! Only available with the `embedded-hal` feature.
+
+use core::fmt;
+
+use embedded_hal::i2c::I2c;
+
+use crate::{Command, Message, SerializationError};
+
+/// Represents errors that can occur while sending a command.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum SendError<E> {
+    /// The command could not be serialized.
+    Serialization(SerializationError),
+    /// The I2C write failed.
+    I2c(E),
+}
+
+impl<E> From<SerializationError> for SendError<E> {
+    fn from(error: SerializationError) -> Self {
+        Self::Serialization(error)
+    }
+}
+
+impl<E: fmt::Debug> fmt::Display for SendError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Serialization(error) => error.fmt(f),
+            Self::I2c(error) => write!(f, "I2C write failed: {error:?}"),
+        }
+    }
+}
+
+impl<E: fmt::Debug> core::error::Error for SendError<E> {}
+
+/// Serializes a command into a stack buffer and writes it to the device at `address`.
+pub fn send<I: I2c, C: Command>(
+    i2c: &mut I,
+    address: u8,
+    command: &C,
+) -> Result<(), SendError<I::Error>> {
+    let message = Message::new(command)?;
+    i2c.write(address, message.as_slice())
+        .map_err(SendError::I2c)
+}
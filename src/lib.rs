@@ -1,15 +1,18 @@
 //! A `no-std`, hardware-agnostic library for serializing Monome II protocol commands.
 //!
 //! This crate provides type-safe structures for II-protocol commands for various
-//! Eurorack modules. Its sole purpose is to serialize these high-level commands
+//! Eurorack modules. Its main purpose is to serialize these high-level commands
 //! into the correct byte sequences. It does not handle I2C communication itself.
+//! The [`Parse`] trait provides the inverse, for tools such as bus sniffers or
+//! software followers.
 //!
 //! ## Usage
 //!
 //! 1.  Choose a module from the library, like `er301`.
 //! 2.  Instantiate a command enum, e.g., `er301::Commands::SetCv { ... }`.
 //! 3.  Create a buffer to hold the serialized message. The `Command::MAX_LENGTH`
-//!     associated constant can help you size this appropriately.
+//!     associated constant can help you size this appropriately, and `Command::len`
+//!     returns the exact size of a given command.
 //! 4.  Call the `.to_bytes()` method on your command object.
 //! 5.  If successful, you get a byte slice ready to be sent over I2C.
 //!
@@ -33,13 +36,238 @@
 
 #![cfg_attr(not(test), no_std)]
 
+mod address;
+mod any;
+#[cfg(test)]
+mod byte_order;
+pub mod conv;
+mod cursor;
+pub mod debug;
 pub mod devices;
+#[cfg(test)]
+mod golden;
+#[cfg(feature = "embedded-hal")]
+pub mod hal;
+mod message;
+#[cfg(test)]
+mod opcodes;
+#[cfg(test)]
+mod proptests;
+mod raw;
+mod sink;
+mod teletype_op;
+mod transaction;
+pub mod units;
+
+use core::fmt;
+
+pub use address::{AddressConflict, DeviceKind, check_addresses};
+pub use any::AnyCommand;
+use cursor::Cursor;
+use devices::*;
+pub use message::Message;
+pub use raw::Raw;
+pub use sink::{ByteSink, SinkFull, WriteError};
+pub use teletype_op::TeletypeOp;
+pub use transaction::{Transaction, TransactionError};
+
+/// The longest message any supported device command serializes to.
+/// A buffer of this size can hold any command from the `devices` modules.
+pub const MAX_MESSAGE_LENGTH: usize = {
+    let mut max = 0;
+    let mut i = 0;
+    while i < ALL_DEVICES.len() {
+        if ALL_DEVICES[i].max_command_length > max {
+            max = ALL_DEVICES[i].max_command_length;
+        }
+        i += 1;
+    }
+    max
+};
+
+/// Metadata describing a supported device, e.g. for building UIs or validating addresses.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeviceDescriptor {
+    /// The human-readable name of the device.
+    pub name: &'static str,
+    /// The address of the first device, or the only one for fixed-address devices.
+    pub default_address: u8,
+    /// All addresses the device can be configured to answer on.
+    pub address_range: core::ops::RangeInclusive<u8>,
+    /// The longest message any of the device's commands serializes to.
+    pub max_command_length: usize,
+    /// Whether the device has CV outputs that can be set over II.
+    pub has_cv: bool,
+    /// Whether the device has gate or trigger outputs that can be set over II.
+    pub has_gates: bool,
+    /// Whether the device has oscillators that can be controlled over II.
+    pub has_osc: bool,
+    /// Whether the device has envelopes whose stages can be set over II.
+    pub has_envelope: bool,
+    /// The number of CV outputs addressable over II, or 0 without CV outputs.
+    pub cv_channels: u8,
+    /// The number of gate or trigger outputs addressable over II, or 0 without them.
+    pub gate_channels: u8,
+}
+
+/// The descriptors of all supported devices.
+pub const ALL_DEVICES: &[&DeviceDescriptor] = &[
+    &ansible::DESCRIPTOR,
+    &crow::DESCRIPTOR,
+    &disting_ex::DESCRIPTOR,
+    &er301::DESCRIPTOR,
+    &faderbank::DESCRIPTOR,
+    &fh2::DESCRIPTOR,
+    &just_friends::DESCRIPTOR,
+    &teletype::DESCRIPTOR,
+    &telexi::DESCRIPTOR,
+    &telexo::DESCRIPTOR,
+    &w::DESCRIPTOR,
+];
 
 /// Represents errors that can occur during command serialization.
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum SerializationError {
     /// The provided buffer was too small to serialize the command.
-    BufferTooSmall,
+    BufferTooSmall {
+        /// The number of bytes the buffer needed to hold.
+        needed: usize,
+        /// The number of bytes the buffer actually had.
+        got: usize,
+    },
+    /// A field holds a value outside the range documented for the command.
+    /// Only returned when the `strict` feature is enabled.
+    ValueOutOfRange {
+        /// The name of the offending field.
+        field: &'static str,
+    },
+}
+
+impl SerializationError {
+    /// Creates a `BufferTooSmall` error for a buffer of `got` bytes that needed `needed`.
+    pub const fn buffer_too_small(needed: usize, got: usize) -> Self {
+        Self::BufferTooSmall { needed, got }
+    }
+}
+
+impl fmt::Display for SerializationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::BufferTooSmall { needed, got } => {
+                write!(f, "buffer too small: needed {needed} bytes, got {got}")
+            }
+            Self::ValueOutOfRange { field } => {
+                write!(f, "`{field}` is outside its documented range")
+            }
+        }
+    }
+}
+
+impl core::error::Error for SerializationError {}
+
+/// Represents errors that can occur while parsing a command from bytes.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum DeserializationError {
+    /// The first byte does not match any opcode known for the device.
+    UnknownOpcode(u8),
+    /// The input ended before the command was complete.
+    UnexpectedEof,
+    /// A field holds a value that the command cannot represent, e.g. a boolean that is
+    /// neither 0 nor 1.
+    InvalidValue,
+}
+
+impl fmt::Display for DeserializationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::UnknownOpcode(opcode) => write!(f, "unknown opcode 0x{opcode:02X}"),
+            Self::UnexpectedEof => f.write_str("input ended before the command was complete"),
+            Self::InvalidValue => f.write_str("a field holds a value the command cannot represent"),
+        }
+    }
+}
+
+impl core::error::Error for DeserializationError {}
+
+/// Represents errors that can occur while building a command with a checked constructor.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum BuildError {
+    /// A field holds a value outside the range documented for the command.
+    OutOfRange {
+        /// The name of the offending field.
+        field: &'static str,
+    },
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::OutOfRange { field } => write!(f, "`{field}` is outside its documented range"),
+        }
+    }
+}
+
+impl core::error::Error for BuildError {}
+
+/// A coarse classification of commands, e.g. for prioritizing or rate-limiting II traffic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CommandCategory {
+    /// Sets a CV output or another continuously changing value.
+    Cv,
+    /// Sets, toggles or fires a gate or trigger, or plays a note.
+    Gate,
+    /// Configures an oscillator or voice.
+    Osc,
+    /// Configures or fires an envelope.
+    Envelope,
+    /// Loads, saves or selects a preset, or resets the configuration.
+    Preset,
+    /// Changes a mode, parameter or other device configuration.
+    Mode,
+    /// Controls a clock, metronome, sequencer or tape transport.
+    Transport,
+}
+
+/// Identifies the destination a command writes to, for coalescing redundant commands.
+///
+/// Two commands for the same device with equal keys set the same value, so a host that
+/// queues several of them in one tick only needs to send the last. Each device's
+/// `Commands::target_key` returns it, usually built from the opcode and the port or
+/// channel field. Commands that set the same destination in different ways share a key,
+/// e.g. an ER-301 `SetCv` and `SetCvImmediate` to the same port. Keys of different
+/// devices are unrelated; [`AnyCommand::target_key`] pairs them with the address.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct TargetKey {
+    opcode: u8,
+    channel: u8,
+    index: u8,
+}
+
+impl TargetKey {
+    /// Creates a key for the destination addressed by `channel`, or by the opcode alone
+    /// for commands without one.
+    pub(crate) const fn new(opcode: u8, channel: u8) -> Self {
+        Self {
+            opcode,
+            channel,
+            index: 0,
+        }
+    }
+
+    /// Creates a key for a destination addressed by two fields, e.g. a track and a step.
+    pub(crate) const fn with_index(opcode: u8, channel: u8, index: u8) -> Self {
+        Self {
+            opcode,
+            channel,
+            index,
+        }
+    }
 }
 
 /// The core trait for any object that can be serialized into an II-compatible byte message.
@@ -52,7 +280,1837 @@ pub trait Command {
     ///
     /// On success, it returns a slice of the buffer containing only the written bytes.
     fn to_bytes<'a>(&self, buffer: &'a mut [u8]) -> Result<&'a [u8], SerializationError>;
+
+    /// Returns the exact number of bytes this command serializes to.
+    ///
+    /// This is never more than `MAX_LENGTH` and lets callers size buffers precisely
+    /// without serializing the command first.
+    fn len(&self) -> usize;
+
+    /// Returns `true` if the command serializes to zero bytes.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Resolves the I2C address the command is sent to, so generic code needs no
+    /// per-device addressing rules.
+    ///
+    /// - Fixed-address devices (Ansible, FH-2, Just Friends, W/) ignore `index` and always
+    ///   return their address. Crow does too, returning its default address.
+    /// - Devices whose address is set by jumper or menu (Disting EX, ER-301, Teletype,
+    ///   TXi, TXo) treat `index` like their module's `address` function does, returning
+    ///   `None` if it is out of range.
+    /// - [`AnyCommand`] ignores `index` in favor of the one it carries.
+    ///
+    /// The default returns `None`, for commands with no address of their own, such as
+    /// [`Raw`] and 16n Faderbank messages, which go to the follower's address.
+    fn address(&self, index: u8) -> Option<u8> {
+        let _ = index;
+        None
+    }
+
+    /// Serializes the command and pushes its bytes into `sink`, returning how many were
+    /// written.
+    ///
+    /// The command is validated and serialized in full before the first byte is pushed,
+    /// but a sink that fills up midway keeps the bytes it already took.
+    ///
+    /// The default serializes into a stack buffer of [`MAX_MESSAGE_LENGTH`] bytes, so it
+    /// fails to compile for commands whose `MAX_LENGTH` is longer. Such commands must
+    /// override this method, as [`Raw`] does.
+    fn write_into<S: ByteSink>(&self, sink: &mut S) -> Result<usize, WriteError<S::Error>> {
+        const { assert!(Self::MAX_LENGTH <= MAX_MESSAGE_LENGTH) };
+        let mut buffer = [0u8; MAX_MESSAGE_LENGTH];
+        let bytes = self.to_bytes(&mut buffer)?;
+        for &byte in bytes {
+            sink.push(byte).map_err(WriteError::Sink)?;
+        }
+        Ok(bytes.len())
+    }
+
+    /// Serializes the command into an owned `heapless::Vec` with capacity `N`.
+    ///
+    /// `N` is usually `MAX_LENGTH` or [`MAX_MESSAGE_LENGTH`]. Returns
+    /// `SerializationError::BufferTooSmall` if the command does not fit.
+    #[cfg(feature = "heapless")]
+    fn to_vec<const N: usize>(&self) -> Result<heapless::Vec<u8, N>, SerializationError> {
+        let mut vec = heapless::Vec::new();
+        vec.resize(self.len(), 0)
+            .map_err(|_| SerializationError::buffer_too_small(self.len(), N))?;
+        let len = self.to_bytes(&mut vec)?.len();
+        vec.truncate(len);
+        Ok(vec)
+    }
+}
+
+/// Creates a zeroed byte array sized for the longest command of the given type.
+///
+/// This expands to `[0u8; <$command as Command>::MAX_LENGTH]`. A `TxBuffer<C>` type with
+/// the same effect would need the unstable `generic_const_exprs` feature to size an
+/// array from `C::MAX_LENGTH`, while the macro works on stable Rust because the command
+/// type is concrete where it is used.
+///
+/// ```
+/// use mii::{Command, devices::er301, tx_buffer};
+///
+/// let mut buffer = tx_buffer!(er301::Commands);
+/// let command = er301::Commands::SetCv { port: 5, value: 8192 };
+/// assert_eq!(command.to_bytes(&mut buffer)?, &[0x11, 0x05, 0x20, 0x00]);
+/// # Ok::<(), mii::SerializationError>(())
+/// ```
+#[macro_export]
+macro_rules! tx_buffer {
+    ($command:ty) => {
+        [0u8; <$command as $crate::Command>::MAX_LENGTH]
+    };
+}
+
+/// Serializes a sequence of commands at compile time, e.g. to store it in flash.
+///
+/// Expands to a constant `([u8; N], [u8; M])` for `M` commands totalling `N` bytes. The
+/// first array holds the serialized commands back to back, in order, and the second the
+/// length of each one, so a player walks the bytes by slicing off one length at a time.
+/// Commands may belong to different devices. Each is serialized with its
+/// `to_bytes_array`, so, as there, the fields are not range-checked.
+///
+/// ```
+/// use mii::devices::{er301, telexo};
+/// use mii::seq;
+///
+/// const INTRO: ([u8; 6], [u8; 2]) = seq![
+///     er301::Commands::SetCv { port: 5, value: 8192 },
+///     telexo::Commands::SetTrPulse { port: 2 },
+/// ];
+///
+/// let (bytes, lengths) = INTRO;
+/// let mut rest = &bytes[..];
+/// for len in lengths {
+///     let (message, tail) = rest.split_at(usize::from(len));
+///     // i2c.write(address, message);
+///     rest = tail;
+/// }
+/// assert_eq!(bytes, [0x11, 0x05, 0x20, 0x00, 0x14, 0x02]);
+/// assert_eq!(lengths, [4, 2]);
+/// ```
+#[macro_export]
+macro_rules! seq {
+    ($($command:expr),* $(,)?) => {{
+        const LENGTHS: &[usize] = &[$($command.to_bytes_array().1),*];
+        const COUNT: usize = LENGTHS.len();
+        const TOTAL: usize = {
+            let mut total = 0;
+            let mut i = 0;
+            while i < COUNT {
+                total += LENGTHS[i];
+                i += 1;
+            }
+            total
+        };
+        // The cursors are left unused by an empty sequence and unread after the last command.
+        #[allow(unused_mut, unused_variables, unused_assignments)]
+        const SEQ: ([u8; TOTAL], [u8; COUNT]) = {
+            let mut bytes = [0u8; TOTAL];
+            let mut lengths = [0u8; COUNT];
+            let mut offset = 0;
+            let mut index = 0;
+            $(
+                let (command, len) = $command.to_bytes_array();
+                let mut i = 0;
+                while i < len {
+                    bytes[offset + i] = command[i];
+                    i += 1;
+                }
+                lengths[index] = len as u8;
+                offset += len;
+                index += 1;
+            )*
+            (bytes, lengths)
+        };
+        SEQ
+    }};
+}
+
+/// Returns the number of bytes `commands` serialize to back to back, e.g. to size the
+/// buffer for [`serialize_all`] or a `heapless::Vec` exactly.
+///
+/// This sums [`Command::len`], so nothing is serialized.
+pub fn total_len<C: Command>(commands: &[C]) -> usize {
+    commands.iter().map(C::len).sum()
+}
+
+/// Serializes several commands back to back into the provided byte buffer.
+///
+/// On success, it returns a slice of the buffer containing the bytes of all commands,
+/// ready to be flushed as one frame of events. If the buffer is too small, the error
+/// reports the length needed for all commands, not just the one that did not fit.
+pub fn serialize_all<'a, C: Command>(
+    commands: &[C],
+    buffer: &'a mut [u8],
+) -> Result<&'a [u8], SerializationError> {
+    let got = buffer.len();
+    let mut cursor = Cursor::new(buffer);
+    for (i, command) in commands.iter().enumerate() {
+        match cursor.push_command(command) {
+            Ok(()) => {}
+            Err(SerializationError::BufferTooSmall { .. }) => {
+                let needed = cursor.position() + total_len(&commands[i..]);
+                return Err(SerializationError::buffer_too_small(needed, got));
+            }
+            Err(error) => return Err(error),
+        }
+    }
+    Ok(cursor.finish())
+}
+
+/// Serializes commands one at a time into a reused scratch buffer.
+///
+/// Each call to [`FrameIter::next_frame`] overwrites the buffer with the next command,
+/// so a scheduler can pull frames on demand, e.g. to hand each one to a DMA transfer,
+/// without allocating or serializing the whole stream up front. It does not implement
+/// `Iterator`, since every frame borrows the scratch buffer until the next call.
+pub struct FrameIter<'b, I> {
+    commands: I,
+    buffer: &'b mut [u8],
+}
+
+impl<'b, 'c, C: Command + 'c, I: Iterator<Item = &'c C>> FrameIter<'b, I> {
+    /// Creates an iterator over `commands` that serializes into `buffer`.
+    pub fn new<T: IntoIterator<IntoIter = I>>(commands: T, buffer: &'b mut [u8]) -> Self {
+        Self {
+            commands: commands.into_iter(),
+            buffer,
+        }
+    }
+
+    /// Serializes the next command and returns its bytes.
+    ///
+    /// Returns `None` once every command has been serialized.
+    pub fn next_frame(&mut self) -> Option<Result<&[u8], SerializationError>> {
+        let command = self.commands.next()?;
+        Some(command.to_bytes(self.buffer))
+    }
+}
+
+/// The counterpart to [`Command`] for objects that can be parsed from an II-compatible
+/// byte message.
+pub trait Parse: Sized {
+    /// Parses a command from the start of `bytes`.
+    ///
+    /// On success, it returns the decoded command and the number of bytes it consumed.
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, usize), DeserializationError>;
+}
+
+/// Ensures a field is within its documented range.
+pub(crate) fn check_range<T: PartialOrd>(
+    field: &'static str,
+    value: T,
+    range: core::ops::RangeInclusive<T>,
+) -> Result<(), SerializationError> {
+    if range.contains(&value) {
+        Ok(())
+    } else {
+        Err(SerializationError::ValueOutOfRange { field })
+    }
+}
+
+/// Ensures a field passed to a checked constructor is within its documented range.
+pub(crate) fn check_build_range<T: PartialOrd>(
+    field: &'static str,
+    value: T,
+    range: core::ops::RangeInclusive<T>,
+) -> Result<(), BuildError> {
+    if range.contains(&value) {
+        Ok(())
+    } else {
+        Err(BuildError::OutOfRange { field })
+    }
+}
+
+/// Writes a 16-bit field at `offset`. Multi-byte II fields are big-endian, most
+/// significant byte first.
+///
+/// Every multi-byte field in the supported command maps is 16 bits wide, including Disting
+/// EX parameter values and W/ tape positions, so there are no 32-bit counterparts.
+pub(crate) const fn put_u16_be(buffer: &mut [u8], offset: usize, value: u16) {
+    let bytes = value.to_be_bytes();
+    buffer[offset] = bytes[0];
+    buffer[offset + 1] = bytes[1];
+}
+
+/// Writes a signed 16-bit field at `offset` in two's complement, like [`put_u16_be`].
+pub(crate) const fn put_i16_be(buffer: &mut [u8], offset: usize, value: i16) {
+    put_u16_be(buffer, offset, value as u16);
+}
+
+/// Decodes a boolean field, which is serialized as 0 or 1.
+pub(crate) fn parse_bool(byte: u8) -> Result<bool, DeserializationError> {
+    match byte {
+        0 => Ok(false),
+        1 => Ok(true),
+        _ => Err(DeserializationError::InvalidValue),
+    }
 }
 
 #[cfg(test)]
-mod tests {}
+mod tests {
+    use super::*;
+    use core::fmt::Debug;
+
+    fn assert_round_trip<C: Command + Parse + PartialEq + Debug>(commands: &[C]) {
+        let mut buffer = [0u8; 16];
+        for command in commands {
+            let bytes = command.to_bytes(&mut buffer).unwrap();
+            let (parsed, consumed) = C::from_bytes(bytes).unwrap();
+            assert_eq!(&parsed, command);
+            assert_eq!(consumed, bytes.len());
+            assert_short_buffers_are_rejected(command);
+        }
+    }
+
+    /// Serializes into every buffer size from 0 to 8 bytes, which must never panic and
+    /// must fail with `BufferTooSmall` exactly when the command does not fit.
+    fn assert_short_buffers_are_rejected<C: Command + Debug>(command: &C) {
+        let mut buffer = [0u8; 8];
+        for size in 0..buffer.len() {
+            match command.to_bytes(&mut buffer[..size]) {
+                Ok(bytes) => assert_eq!(bytes.len(), command.len(), "{command:?}"),
+                Err(SerializationError::BufferTooSmall { needed, got }) => {
+                    assert!(size < command.len(), "{command:?} with {size} bytes");
+                    assert_eq!((needed, got), (command.len(), size), "{command:?}");
+                }
+                Err(error) => panic!("{command:?} with {size} bytes: {error:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn ansible_round_trip() {
+        use ansible::Commands::*;
+        assert_round_trip(&[
+            SetCv {
+                port: 3,
+                value: -4096,
+            },
+            SetCvSlew { port: 1, ms: 500 },
+            SetCvFromFader {
+                device_port: 2,
+                value: 40000,
+            },
+            SetTrState {
+                port: 0,
+                state: true,
+            },
+            SetTrToggle { port: 1 },
+            SetTrPulse { port: 2 },
+            SetTrPulseDuration { port: 3, ms: 20 },
+            LoadPreset { preset: 7 },
+            SavePreset { preset: 1 },
+            KriaSetStep {
+                track: 4,
+                step: 15,
+                state: 2,
+            },
+            KriaSetTempo { bpm: 128 },
+            KriaSelectPattern { pattern: 5 },
+            KriaMute {
+                track: 2,
+                state: true,
+            },
+            KriaTranspose {
+                track: 1,
+                semitones: -7,
+            },
+            MeadowphysicsStart,
+            MeadowphysicsStop,
+            MeadowphysicsSetSpeed { row: 6, speed: 300 },
+            MeadowphysicsReset { row: 0 },
+            SetTrPolarity {
+                port: 2,
+                polarity: false,
+            },
+            LevelsSetValue {
+                track: 3,
+                value: 4096,
+            },
+            LevelsReset,
+            CyclesSetFreq {
+                channel: 2,
+                freq: 440,
+            },
+            CyclesReset,
+            RequestCurrentPreset,
+        ]);
+    }
+
+    #[test]
+    fn crow_round_trip() {
+        use crow::Commands::*;
+        assert_round_trip(&[
+            SetOutput {
+                channel: 1,
+                value: 8192,
+            },
+            CallFunction { index: 4, arg: -1 },
+            Query { index: 2 },
+        ]);
+    }
+
+    #[test]
+    fn disting_ex_round_trip() {
+        use disting_ex::Commands::*;
+        assert_round_trip(&[
+            SelectAlgorithm { index: 12 },
+            SetParameter {
+                param: 3,
+                value: -100,
+            },
+            PlaySample { slot: 9 },
+            SetZModeParameter {
+                param: 1,
+                value: 1000,
+            },
+            LoadPreset { index: 12 },
+            SavePreset { index: 12 },
+            SelectSdFile { folder: 2, file: 7 },
+            NoteOn {
+                note: 60,
+                velocity: 100,
+            },
+            NoteOff { note: 60 },
+        ]);
+    }
+
+    #[test]
+    fn er301_round_trip() {
+        use er301::Commands::*;
+        assert_round_trip(&[
+            SetGate {
+                port: 99,
+                state: true,
+            },
+            SetTrToggle { port: 4 },
+            SetTrPulse { port: 5 },
+            SetTrTime { port: 6, ms: 1000 },
+            SetTrPolarity {
+                port: 7,
+                polarity: false,
+            },
+            SetCv {
+                port: 5,
+                value: 8192,
+            },
+            SetCvImmediate {
+                port: 5,
+                value: -8192,
+            },
+            SetCvSlew { port: 5, ms: 250 },
+        ]);
+    }
+
+    #[test]
+    fn faderbank_round_trip() {
+        assert_round_trip(&[faderbank::Commands::from_fader(13, 16383)]);
+    }
+
+    #[test]
+    fn fh2_round_trip() {
+        use fh2::Commands::*;
+        assert_round_trip(&[
+            SetGate {
+                channel: 40,
+                state: true,
+            },
+            SetCv {
+                channel: 63,
+                value: -16384,
+            },
+            SetClockDivision { value: 4 },
+            SetClockBpm { bpm: 120 },
+            Start,
+            Stop,
+            Continue,
+        ]);
+    }
+
+    #[test]
+    fn just_friends_round_trip() {
+        use just_friends::Commands::*;
+        assert_round_trip(&[
+            SetGate {
+                output: 0,
+                state: true,
+            },
+            PlayNote {
+                output: 6,
+                pitch: -1638,
+                volume: 8000,
+            },
+            SetVoice {
+                channel: 2,
+                pitch: 1638,
+                level: 4000,
+            },
+            SetIntone {
+                channel: 0,
+                ratio: -2000,
+            },
+            SetMode { on: true },
+            SetRun { value: 3000 },
+            SetTranspose { value: -1638 },
+            SetRamp { value: 100 },
+            SetCurve { value: -100 },
+            SetFm { value: 0 },
+            SetQuantize { on: true },
+            Tune {
+                channel: 3,
+                value: -27,
+            },
+            SetRunMode { mode: 1 },
+            SetGodMode { on: true },
+            SetTime {
+                channel: 2,
+                value: 4096,
+            },
+        ]);
+    }
+
+    #[test]
+    fn teletype_round_trip() {
+        use teletype::Commands::*;
+        assert_round_trip(&[
+            SetScript { script: 10 },
+            SetTrigger { script: 3 },
+            SetPattern {
+                index: 63,
+                value: -1000,
+            },
+        ]);
+    }
+
+    #[test]
+    fn telexi_round_trip() {
+        use telexi::Commands::*;
+        assert_round_trip(&[
+            SetInputScale {
+                port: 1,
+                min: -1000,
+                max: 1000,
+            },
+            SetInputMap {
+                port: 2,
+                top: 16383,
+                bottom: -16384,
+            },
+            SetParamQuantize { port: 3, scale: 4 },
+            Store,
+            Reset,
+            RequestInput { port: 2 },
+            RequestParam { port: 1 },
+            SetParamScale {
+                port: 1,
+                min: -1000,
+                max: 1000,
+            },
+            SetParamMap {
+                port: 2,
+                top: 100,
+                bottom: -100,
+            },
+        ]);
+    }
+
+    #[test]
+    fn telexo_round_trip() {
+        use telexo::Commands::*;
+        assert_round_trip(&[
+            SetGate {
+                port: 0,
+                state: true,
+            },
+            SetTrToggle { port: 1 },
+            SetTrPulse { port: 2 },
+            SetTrTime { port: 3, ms: 15 },
+            SetTrPolarity {
+                port: 0,
+                polarity: true,
+            },
+            SetCv { port: 1, value: -1 },
+            SetCvSlew { port: 2, ms: 65535 },
+            SetOscPitch {
+                port: 3,
+                pitch: 2048,
+            },
+            SetOscNote { port: 0, note: 60 },
+            SetOscFreq { port: 1, freq: 440 },
+            SetOscWaveform {
+                port: 2,
+                waveform: 5000,
+            },
+            SetOscWidth { port: 3, width: 50 },
+            SetOscRectify { port: 0, mode: -3 },
+            SetEnvelopeMode {
+                port: 1,
+                enabled: true,
+            },
+            SetEnvelopeAttack { port: 2, ms: 10 },
+            SetEnvelopeDecay { port: 3, ms: 2000 },
+            SetEnvelopeState { port: 0, on: false },
+            SetMetroTime { ms: 500 },
+            SetMetroActive { state: true },
+            SetMetroBpm { bpm: 120 },
+            SetCvOffset {
+                port: 1,
+                value: -1638,
+            },
+            SetCvQuantizedNote { port: 2, note: 36 },
+            OscSync { port: 3 },
+            SetOscPhase {
+                port: 0,
+                phase: 32768,
+            },
+            SetOscLfoMode {
+                port: 1,
+                enabled: true,
+            },
+            SetOscLfoRate {
+                port: 2,
+                rate: -300,
+            },
+            SetEnvelopeEor { port: 0, dest: 2 },
+            SetEnvelopeEoc { port: 1, dest: 255 },
+            SetOscSlew { port: 3, ms: 250 },
+            SetCvImmediate {
+                port: 3,
+                value: -8192,
+            },
+            SetOscCenter {
+                port: 1,
+                value: -27,
+            },
+            SetCvScale { port: 2, scale: 3 },
+            Kill,
+            Reset,
+        ]);
+    }
+
+    #[test]
+    fn w_round_trip() {
+        use w::Commands::*;
+        assert_round_trip(&[
+            Play { speed: -2 },
+            Record { state: true },
+            Loop {
+                start: -100,
+                end: 32767,
+            },
+            SetSpeed { value: 1 },
+            SetLoopStart { position: -100 },
+            SetLoopEnd { position: 2000 },
+            CueTo { position: 0 },
+            RecordArm { state: true },
+            SetRecordThreshold { value: 1638 },
+            Erase,
+        ]);
+    }
+
+    #[test]
+    fn short_commands_fit_exact_buffers() {
+        let mut buffer = [0u8; 2];
+        assert_eq!(
+            ansible::Commands::SetTrToggle { port: 1 }.to_bytes(&mut buffer),
+            Ok(&[0x11, 0x01][..])
+        );
+        assert_eq!(
+            telexo::Commands::SetTrToggle { port: 1 }.to_bytes(&mut buffer),
+            Ok(&[0x13, 0x01][..])
+        );
+        assert_eq!(
+            telexo::Commands::SetCv { port: 1, value: 0 }.to_bytes(&mut buffer),
+            Err(SerializationError::BufferTooSmall { needed: 4, got: 2 })
+        );
+    }
+
+    #[test]
+    fn commands_serialize_in_const_context() {
+        const SET_CV: ([u8; 4], usize) = er301::Commands::SetCv {
+            port: 5,
+            value: 8192,
+        }
+        .to_bytes_array();
+        const STORE: ([u8; 6], usize) = telexi::Commands::Store.to_bytes_array();
+
+        assert_eq!(&SET_CV.0[..SET_CV.1], &[0x11, 0x05, 0x20, 0x00]);
+        assert_eq!(&STORE.0[..STORE.1], &[0x20]);
+
+        let command = w::Commands::Loop {
+            start: -2,
+            end: 300,
+        };
+        let (array, len) = command.to_bytes_array();
+        let mut buffer = [0u8; w::Commands::MAX_LENGTH];
+        assert_eq!(command.to_bytes(&mut buffer), Ok(&array[..len]));
+    }
+
+    #[test]
+    fn frame_iter_reuses_the_scratch_buffer() {
+        let commands = [
+            er301::Commands::SetTrPulse { port: 1 },
+            er301::Commands::SetCv {
+                port: 2,
+                value: 0x0304,
+            },
+            er301::Commands::SetGate {
+                port: 3,
+                state: true,
+            },
+        ];
+        let mut buffer = [0u8; er301::Commands::MAX_LENGTH];
+        let mut frames = FrameIter::new(&commands, &mut buffer);
+
+        assert_eq!(frames.next_frame(), Some(Ok(&[0x14, 0x01][..])));
+        assert_eq!(frames.next_frame(), Some(Ok(&[0x11, 0x02, 0x03, 0x04][..])));
+        assert_eq!(frames.next_frame(), Some(Ok(&[0x00, 0x03, 0x01][..])));
+        assert_eq!(frames.next_frame(), None);
+    }
+
+    #[test]
+    fn transaction_rejects_overflow() {
+        let mut buffer = [0u8; 6];
+        let transaction = Transaction::<4>::new(&mut buffer)
+            .push(&w::Commands::Record { state: true })
+            .unwrap()
+            .push(&w::Commands::Play { speed: 1 })
+            .unwrap();
+        assert_eq!(transaction.as_slice(), &[0x02, 0x01, 0x01, 0x00, 0x01]);
+        assert!(matches!(
+            transaction.push(&w::Commands::Play { speed: 1 }),
+            Err(TransactionError::Serialization(
+                SerializationError::BufferTooSmall { needed: 8, got: 6 }
+            ))
+        ));
+
+        let mut buffer = [0u8; 16];
+        let transaction = Transaction::<1>::new(&mut buffer)
+            .push(&w::Commands::Record { state: true })
+            .unwrap();
+        assert!(matches!(
+            transaction.push(&w::Commands::Record { state: false }),
+            Err(TransactionError::TooManyCommands { max: 1 })
+        ));
+    }
+
+    #[test]
+    fn tx_buffers_fit_the_longest_command() {
+        let buffer = tx_buffer!(just_friends::Commands);
+        assert_eq!(buffer.len(), just_friends::Commands::MAX_LENGTH);
+        let buffer = tx_buffer!(AnyCommand);
+        assert_eq!(buffer.len(), MAX_MESSAGE_LENGTH);
+    }
+
+    #[test]
+    fn raw_commands_are_written_verbatim() {
+        let command = Raw::<4>::new(&[0x7F, 0x01, 0x02]).unwrap();
+        assert_eq!(command.len(), 3);
+        assert_eq!(command.as_slice(), &[0x7F, 0x01, 0x02]);
+
+        let mut buffer = [0u8; 3];
+        assert_eq!(command.to_bytes(&mut buffer), Ok(&[0x7F, 0x01, 0x02][..]));
+        assert_eq!(
+            command.to_bytes(&mut buffer[..2]),
+            Err(SerializationError::BufferTooSmall { needed: 3, got: 2 })
+        );
+        assert_eq!(
+            Raw::<2>::new(&[0x7F, 0x01, 0x02]),
+            Err(SerializationError::BufferTooSmall { needed: 3, got: 2 })
+        );
+    }
+
+    #[test]
+    fn any_command_resolves_address_and_delegates() {
+        let queue = [
+            AnyCommand::Er301 {
+                index: 1,
+                cmd: er301::Commands::SetTrPulse { port: 3 },
+            },
+            AnyCommand::Telexo {
+                index: 2,
+                cmd: telexo::Commands::SetCv {
+                    port: 0,
+                    value: 0x0102,
+                },
+            },
+            just_friends::Commands::SetGate {
+                output: 0,
+                state: true,
+            }
+            .into(),
+        ];
+        let addresses: Vec<_> = queue.iter().map(AnyCommand::address).collect();
+        assert_eq!(addresses, [Some(0x32), Some(0x62), Some(0x70)]);
+
+        let mut buffer = [0u8; 16];
+        assert_eq!(
+            serialize_all(&queue, &mut buffer),
+            Ok(&[0x14, 0x03, 0x11, 0x00, 0x01, 0x02, 0x01, 0x00, 0x01][..])
+        );
+        assert_eq!(queue[1].len(), 4);
+
+        let out_of_range = AnyCommand::Telexi {
+            index: 8,
+            cmd: telexi::Commands::Store,
+        };
+        assert_eq!(out_of_range.address(), None);
+    }
+
+    #[test]
+    fn command_address_ignores_index_only_for_fixed_devices() {
+        fn resolve<C: Command>(command: &C, index: u8) -> Option<u8> {
+            command.address(index)
+        }
+
+        let gate = just_friends::Commands::SetMode { on: true };
+        assert_eq!(resolve(&gate, 0), Some(just_friends::ADDRESS));
+        assert_eq!(resolve(&gate, 5), Some(just_friends::ADDRESS));
+
+        let cv = telexo::Commands::SetCv { port: 0, value: 0 };
+        assert_eq!(resolve(&cv, 2), Some(0x62));
+        assert_eq!(resolve(&cv, 8), None);
+
+        let any = AnyCommand::Er301 {
+            index: 1,
+            cmd: er301::Commands::SetTrPulse { port: 3 },
+        };
+        assert_eq!(resolve(&any, 0), Some(0x32));
+
+        assert_eq!(resolve(&Raw::<2>::new(&[0x01]).unwrap(), 0), None);
+        assert_eq!(
+            resolve(
+                &faderbank::Commands::SetCvFromFader {
+                    device_port: 0,
+                    value: 0
+                },
+                0
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn serialize_all_packs_commands_contiguously() {
+        let commands = [
+            er301::Commands::SetCv {
+                port: 0,
+                value: 0x0102,
+            },
+            er301::Commands::SetCv {
+                port: 1,
+                value: 0x0304,
+            },
+            er301::Commands::SetCv {
+                port: 2,
+                value: 0x0506,
+            },
+            er301::Commands::SetCv {
+                port: 3,
+                value: 0x0708,
+            },
+        ];
+        let mut buffer = [0u8; 16];
+        assert_eq!(
+            serialize_all(&commands, &mut buffer),
+            Ok(&[
+                0x11, 0x00, 0x01, 0x02, 0x11, 0x01, 0x03, 0x04, 0x11, 0x02, 0x05, 0x06, 0x11, 0x03,
+                0x07, 0x08,
+            ][..])
+        );
+        assert_eq!(
+            serialize_all(&commands, &mut buffer[..15]),
+            Err(SerializationError::BufferTooSmall {
+                needed: 16,
+                got: 15
+            })
+        );
+    }
+
+    #[test]
+    fn commands_display_as_mnemonics() {
+        let command = er301::Commands::SetCv {
+            port: 5,
+            value: 8192,
+        };
+        assert_eq!(command.to_string(), "ER301.CV 5 8192");
+        assert_eq!(
+            telexo::Commands::SetTrPolarity {
+                port: 1,
+                polarity: false
+            }
+            .to_string(),
+            "TO.TR.POL 1 0"
+        );
+        assert_eq!(telexi::Commands::Store.to_string(), "TI.STORE");
+        assert_eq!(
+            AnyCommand::Er301 {
+                index: 2,
+                cmd: command
+            }
+            .to_string(),
+            "ER301.CV 5 8192"
+        );
+    }
+
+    #[test]
+    fn commands_format_as_teletype_ops() {
+        let op = |command: AnyCommand| command.teletype_op().map(|op| op.to_string());
+        assert_eq!(
+            op(AnyCommand::Er301 {
+                index: 0,
+                cmd: er301::Commands::SetGate {
+                    port: 99,
+                    state: true
+                },
+            }),
+            Some("SC.TR 100 1".into())
+        );
+        assert_eq!(
+            op(AnyCommand::Telexo {
+                index: 0,
+                cmd: telexo::Commands::SetCvSlew { port: 0, ms: 50 },
+            }),
+            Some("TO.CV.SLEW 1 50".into())
+        );
+        assert_eq!(
+            op(ansible::Commands::SetTrPulse { port: 3 }.into()),
+            Some("TR.PULSE 8".into())
+        );
+        assert_eq!(
+            op(crow::Commands::CallFunction { index: 2, arg: -5 }.into()),
+            Some("CROW.C2 -5".into())
+        );
+        assert_eq!(
+            op(just_friends::Commands::PlayNote {
+                output: 0,
+                pitch: 1638,
+                volume: 8000
+            }
+            .into()),
+            Some("JF.NOTE 1638 8000".into())
+        );
+        assert_eq!(
+            op(w::Commands::Play { speed: -2 }.into()),
+            Some("W/.PLAY -2".into())
+        );
+
+        assert_eq!(op(ansible::Commands::LoadPreset { preset: 1 }.into()), None);
+        assert_eq!(
+            op(just_friends::Commands::PlayNote {
+                output: 2,
+                pitch: 0,
+                volume: 0
+            }
+            .into()),
+            None
+        );
+        assert_eq!(op(fh2::Commands::Start.into()), None);
+        assert_eq!(faderbank::Commands::from_fader(0, 100).teletype_op(), None);
+        assert_eq!(
+            telexo::Commands::SetMetroBpm { bpm: 120 }.teletype_op(),
+            None
+        );
+    }
+
+    #[test]
+    fn commands_report_their_category() {
+        assert_eq!(
+            telexo::Commands::SetTrPulse { port: 0 }.category(),
+            CommandCategory::Gate
+        );
+        assert_eq!(
+            telexo::Commands::SetEnvelopeAttack { port: 0, ms: 10 }.category(),
+            CommandCategory::Envelope
+        );
+        assert_eq!(
+            w::Commands::Play { speed: 1 }.category(),
+            CommandCategory::Transport
+        );
+        assert_eq!(
+            AnyCommand::Er301 {
+                index: 0,
+                cmd: er301::Commands::SetCv { port: 0, value: 0 },
+            }
+            .category(),
+            CommandCategory::Cv
+        );
+    }
+
+    /// Returns the longest command that parses from a zero-filled payload, which covers
+    /// every variant since each opcode maps to exactly one of them.
+    fn longest_parsed_command<C: Command + Parse>() -> usize {
+        let mut bytes = [0u8; 16];
+        (0..=u8::MAX)
+            .filter_map(|opcode| {
+                bytes[0] = opcode;
+                C::from_bytes(&bytes).ok()
+            })
+            .map(|(_, len)| len)
+            .max()
+            .unwrap_or(0)
+    }
+
+    #[test]
+    fn max_length_matches_longest_command() {
+        fn check<C: Command + Parse>() {
+            let device = core::any::type_name::<C>();
+            assert_eq!(longest_parsed_command::<C>(), C::MAX_LENGTH, "{device}");
+        }
+        check::<ansible::Commands>();
+        check::<crow::Commands>();
+        check::<disting_ex::Commands>();
+        check::<er301::Commands>();
+        check::<faderbank::Commands>();
+        check::<fh2::Commands>();
+        check::<just_friends::Commands>();
+        check::<teletype::Commands>();
+        check::<telexi::Commands>();
+        check::<telexo::Commands>();
+        check::<w::Commands>();
+    }
+
+    #[test]
+    fn device_descriptors_match_address_resolvers() {
+        for device in ALL_DEVICES {
+            assert!(device.address_range.contains(&device.default_address));
+            assert!(device.max_command_length <= MAX_MESSAGE_LENGTH);
+        }
+        assert_eq!(telexo::DESCRIPTOR.address_range, 0x60..=0x67);
+        assert_eq!(
+            er301::address(2),
+            Some(*er301::DESCRIPTOR.address_range.end())
+        );
+        assert_eq!(
+            ALL_DEVICES
+                .iter()
+                .map(|device| device.max_command_length)
+                .max(),
+            Some(MAX_MESSAGE_LENGTH)
+        );
+    }
+
+    #[test]
+    fn address_checks_flag_duplicates_and_foreign_addresses() {
+        use DeviceKind::*;
+        let kinds = [
+            Ansible,
+            Crow,
+            DistingEx,
+            Er301,
+            Faderbank,
+            Fh2,
+            JustFriends,
+            Teletype,
+            Telexi,
+            Telexo,
+            W,
+        ];
+        let defaults = kinds.map(|kind| (kind, kind.descriptor().default_address));
+        assert_eq!(check_addresses(&defaults), Ok(()));
+        assert_eq!(kinds.map(|kind| kind.descriptor())[..], *ALL_DEVICES);
+
+        assert_eq!(
+            check_addresses(&[(Telexo, 0x60), (Er301, 0x31), (Telexo, 0x60)]),
+            Err(AddressConflict::Duplicate {
+                first: 0,
+                second: 2,
+                address: 0x60
+            })
+        );
+        assert_eq!(
+            check_addresses(&[(DistingEx, 0x41), (Er301, 0x41)]),
+            Err(AddressConflict::OutOfRange {
+                entry: 1,
+                kind: Er301,
+                address: 0x41
+            })
+        );
+        assert_eq!(
+            AddressConflict::OutOfRange {
+                entry: 1,
+                kind: Er301,
+                address: 0x41
+            }
+            .to_string(),
+            "entry 1 uses address 0x41, which the ER-301 cannot answer on"
+        );
+
+        // A Crow reassigned from Lua answers on 0x02-0x04.
+        assert_eq!(check_addresses(&[(Crow, 0x01), (Crow, 0x03)]), Ok(()));
+        assert_eq!(
+            check_addresses(&[(Crow, 0x05)]),
+            Err(AddressConflict::OutOfRange {
+                entry: 0,
+                kind: Crow,
+                address: 0x05
+            })
+        );
+    }
+
+    #[test]
+    fn capabilities_match_documented_channel_ranges() {
+        for device in ALL_DEVICES {
+            assert_eq!(device.has_cv, device.cv_channels > 0, "{}", device.name);
+            assert_eq!(
+                device.has_gates,
+                device.gate_channels > 0,
+                "{}",
+                device.name
+            );
+        }
+
+        #[cfg(feature = "strict")]
+        {
+            /// Checks that the last channel is accepted and the one after it rejected.
+            fn check<C: Command>(last: C, past_last: C) {
+                let mut buffer = [0u8; MAX_MESSAGE_LENGTH];
+                assert!(last.to_bytes(&mut buffer).is_ok());
+                assert!(matches!(
+                    past_last.to_bytes(&mut buffer),
+                    Err(SerializationError::ValueOutOfRange { .. })
+                ));
+            }
+
+            let cv = |port| ansible::Commands::SetCv { port, value: 0 };
+            let gate = |port| ansible::Commands::SetTrState { port, state: true };
+            let channels = ansible::DESCRIPTOR.cv_channels;
+            check(cv(channels - 1), cv(channels));
+            let channels = ansible::DESCRIPTOR.gate_channels;
+            check(gate(channels - 1), gate(channels));
+
+            let cv = |port| er301::Commands::SetCv { port, value: 0 };
+            let gate = |port| er301::Commands::SetGate { port, state: true };
+            let channels = er301::DESCRIPTOR.cv_channels;
+            check(cv(channels - 1), cv(channels));
+            let channels = er301::DESCRIPTOR.gate_channels;
+            check(gate(channels - 1), gate(channels));
+
+            let cv = |channel| fh2::Commands::SetCv { channel, value: 0 };
+            let gate = |channel| fh2::Commands::SetGate {
+                channel,
+                state: true,
+            };
+            let channels = fh2::DESCRIPTOR.cv_channels;
+            check(cv(channels - 1), cv(channels));
+            let channels = fh2::DESCRIPTOR.gate_channels;
+            check(gate(channels - 1), gate(channels));
+
+            let cv = |port| telexo::Commands::SetCv { port, value: 0 };
+            let gate = |port| telexo::Commands::SetGate { port, state: true };
+            let channels = telexo::DESCRIPTOR.cv_channels;
+            check(cv(channels - 1), cv(channels));
+            let channels = telexo::DESCRIPTOR.gate_channels;
+            check(gate(channels - 1), gate(channels));
+
+            // Crow and Just Friends count their outputs from 1.
+            let cv = |channel| crow::Commands::SetOutput { channel, value: 0 };
+            let channels = crow::DESCRIPTOR.cv_channels;
+            check(cv(channels), cv(channels + 1));
+
+            let gate = |output| just_friends::Commands::SetGate {
+                output,
+                state: true,
+            };
+            let channels = just_friends::DESCRIPTOR.gate_channels;
+            check(gate(channels), gate(channels + 1));
+        }
+    }
+
+    #[test]
+    fn target_keys_group_commands_that_overwrite_each_other() {
+        use er301::Commands::*;
+        let key = |command: er301::Commands| command.target_key();
+        assert_eq!(
+            key(SetCv { port: 3, value: 0 }),
+            key(SetCvImmediate {
+                port: 3,
+                value: 8192
+            })
+        );
+        assert_ne!(
+            key(SetCv { port: 3, value: 0 }),
+            key(SetCv { port: 4, value: 0 })
+        );
+        assert_ne!(
+            key(SetCv { port: 3, value: 0 }),
+            key(SetCvSlew { port: 3, ms: 0 })
+        );
+        assert_eq!(key(SetTrPulse { port: 3 }), None);
+
+        assert_eq!(
+            telexo::Commands::SetMetroBpm { bpm: 120 }.target_key(),
+            telexo::Commands::SetMetroTime { ms: 500 }.target_key()
+        );
+        assert_ne!(
+            ansible::Commands::KriaSetStep {
+                track: 0,
+                step: 1,
+                state: 1
+            }
+            .target_key(),
+            ansible::Commands::KriaSetStep {
+                track: 1,
+                step: 0,
+                state: 1
+            }
+            .target_key()
+        );
+
+        // The same command for two ER-301 units writes to different destinations.
+        let command = SetCv { port: 3, value: 0 };
+        let first = AnyCommand::Er301 {
+            index: 0,
+            cmd: command,
+        };
+        let second = AnyCommand::Er301 {
+            index: 1,
+            cmd: command,
+        };
+        assert_eq!(
+            first.target_key(),
+            Some((0x31, command.target_key().unwrap()))
+        );
+        assert_ne!(first.target_key(), second.target_key());
+    }
+
+    #[test]
+    fn er301_units_match_indexed_addresses() {
+        assert_eq!(er301::ADDRESS, er301::Unit::A.address());
+        assert_eq!(er301::address(1), Some(er301::Unit::B.address()));
+        assert_eq!(er301::Unit::C.address(), 0x33);
+    }
+
+    #[test]
+    fn typed_constructors_fill_matching_fields() {
+        use units::{QuantizedNote, RawCv};
+        assert_eq!(
+            telexo::Commands::set_cv_quantized_note(1, QuantizedNote(36)),
+            telexo::Commands::SetCvQuantizedNote { port: 1, note: 36 }
+        );
+        assert_eq!(
+            er301::Commands::set_cv(5, RawCv(8192)),
+            er301::Commands::SetCv {
+                port: 5,
+                value: 8192
+            }
+        );
+    }
+
+    #[test]
+    fn just_friends_outputs_name_the_broadcast_case() {
+        use just_friends::{ALL_OUTPUTS, Commands, Output};
+        assert_eq!(
+            Commands::play_note(Output::All, 1638, 8192),
+            Ok(Commands::PlayNote {
+                output: ALL_OUTPUTS,
+                pitch: 1638,
+                volume: 8192
+            })
+        );
+        assert_eq!(
+            Commands::set_gate(Output::One(6), true),
+            Ok(Commands::SetGate {
+                output: 6,
+                state: true
+            })
+        );
+        assert_eq!(
+            u8::try_from(Output::One(0)),
+            Err(BuildError::OutOfRange { field: "output" })
+        );
+        assert_eq!(
+            Commands::set_gate(Output::One(7), false),
+            Err(BuildError::OutOfRange { field: "output" })
+        );
+    }
+
+    #[cfg(feature = "strict")]
+    #[test]
+    fn just_friends_rejects_outputs_past_six() {
+        let mut buffer = [0u8; just_friends::Commands::MAX_LENGTH];
+        let note = |output| just_friends::Commands::PlayNote {
+            output,
+            pitch: 0,
+            volume: 8192,
+        };
+        assert_eq!(
+            note(just_friends::ALL_OUTPUTS).to_bytes(&mut buffer),
+            Ok(&[0x08, 0x00, 0x00, 0x00, 0x20, 0x00][..])
+        );
+        for output in [7, 200] {
+            assert_eq!(
+                note(output).to_bytes(&mut buffer),
+                Err(SerializationError::ValueOutOfRange { field: "output" })
+            );
+            assert_eq!(
+                just_friends::Commands::SetGate {
+                    output,
+                    state: true
+                }
+                .to_bytes(&mut buffer),
+                Err(SerializationError::ValueOutOfRange { field: "output" })
+            );
+        }
+    }
+
+    #[test]
+    fn total_len_sums_command_lengths() {
+        let commands = [
+            er301::Commands::SetCv { port: 0, value: 0 },
+            er301::Commands::SetTrPulse { port: 1 },
+        ];
+        assert_eq!(total_len(&commands), 6);
+        assert_eq!(total_len::<er301::Commands>(&[]), 0);
+
+        let mut buffer = [0u8; 6];
+        assert_eq!(
+            serialize_all(&commands, &mut buffer).map(<[u8]>::len),
+            Ok(total_len(&commands))
+        );
+    }
+
+    #[test]
+    fn cursor_writes_commands_in_order() {
+        let mut buffer = [0u8; 8];
+        let mut cursor = Cursor::new(&mut buffer);
+        cursor
+            .push_command(&w::Commands::Record { state: true })
+            .unwrap();
+        cursor
+            .push_command(&w::Commands::Loop { start: 1, end: -2 })
+            .unwrap();
+        assert_eq!(cursor.position(), 7);
+        assert_eq!(cursor.finish(), &[0x02, 0x01, 0x03, 0x00, 0x01, 0xFF, 0xFE]);
+    }
+
+    #[test]
+    fn cursor_reports_the_end_offset_when_a_command_does_not_fit() {
+        let mut buffer = [0u8; 5];
+        let mut cursor = Cursor::new(&mut buffer);
+        cursor
+            .push_command(&w::Commands::Record { state: true })
+            .unwrap();
+        // `needed` counts the two bytes already written, and `got` is the whole buffer.
+        assert_eq!(
+            cursor.push_command(&w::Commands::Loop { start: 0, end: 1 }),
+            Err(SerializationError::buffer_too_small(7, 5))
+        );
+        // A command that does not fit is not partially written.
+        assert_eq!(cursor.finish(), &[0x02, 0x01]);
+        assert_eq!(buffer, [0x02, 0x01, 0x00, 0x00, 0x00]);
+    }
+
+    #[test]
+    fn chords_serialize_one_note_per_output() {
+        let mut buffer = [0u8; 18];
+        let notes = [(1, 0, 8192), (2, 546, 8192), (3, 956, 4096)];
+        assert_eq!(
+            just_friends::chord(&notes, &mut buffer),
+            Ok(&[
+                0x08, 0x01, 0x00, 0x00, 0x20, 0x00, //
+                0x08, 0x02, 0x02, 0x22, 0x20, 0x00, //
+                0x08, 0x03, 0x03, 0xBC, 0x10, 0x00,
+            ][..])
+        );
+        assert_eq!(
+            just_friends::chord(&notes, &mut buffer[..12]),
+            Err(SerializationError::buffer_too_small(18, 12))
+        );
+        assert_eq!(
+            just_friends::chord(&[(1, 0, 0), (0, 0, 0)], &mut buffer),
+            Err(SerializationError::ValueOutOfRange { field: "output" })
+        );
+    }
+
+    #[test]
+    fn millis_conversions_round_and_saturate() {
+        use units::{Bpm, Millis};
+        assert_eq!(Millis::from_secs_f32(0.25), Millis(250));
+        assert_eq!(Millis::from_secs_f32(0.0004), Millis(0));
+        assert_eq!(Millis::from_secs_f32(0.0006), Millis(1));
+        assert_eq!(Millis::from_secs_f32(65.535), Millis(u16::MAX));
+        assert_eq!(Millis::from_secs_f32(100.0), Millis(u16::MAX));
+        assert_eq!(Millis::from_secs_f32(-1.0), Millis(0));
+        assert_eq!(Millis::from_secs_f32(f32::NAN), Millis(0));
+        assert_eq!(Millis::from_bpm(Bpm(120)), Millis(500));
+        assert_eq!(Millis::from_bpm(Bpm(7)), Millis(8571));
+        assert_eq!(Millis::from_bpm(Bpm(1)), Millis(60000));
+        assert_eq!(Millis::from_bpm(Bpm(0)), Millis(u16::MAX));
+        assert_eq!(
+            telexo::Commands::set_cv_slew(1, Millis::from_secs_f32(1.5)),
+            telexo::Commands::SetCvSlew { port: 1, ms: 1500 }
+        );
+        assert_eq!(
+            er301::Commands::set_tr_time(3, Millis(10)),
+            er301::Commands::SetTrTime { port: 3, ms: 10 }
+        );
+    }
+
+    #[test]
+    fn glides_set_the_slew_before_the_cv() {
+        let mut buffer = [0u8; 8];
+        assert_eq!(
+            serialize_all(&er301::glide(2, 8192, 250), &mut buffer),
+            Ok(&[0x12, 0x02, 0x00, 0xFA, 0x11, 0x02, 0x20, 0x00][..])
+        );
+        assert_eq!(
+            telexo::glide(1, -1, 50),
+            [
+                telexo::Commands::SetCvSlew { port: 1, ms: 50 },
+                telexo::Commands::SetCv { port: 1, value: -1 },
+            ]
+        );
+    }
+
+    #[test]
+    fn gate_masks_expand_to_one_command_per_output() {
+        let gates: Vec<_> = ansible::Commands::gates_from_mask(0b1111_0101).collect();
+        assert_eq!(
+            gates,
+            [
+                ansible::Commands::SetTrState {
+                    port: 0,
+                    state: true
+                },
+                ansible::Commands::SetTrState {
+                    port: 1,
+                    state: false
+                },
+                ansible::Commands::SetTrState {
+                    port: 2,
+                    state: true
+                },
+                ansible::Commands::SetTrState {
+                    port: 3,
+                    state: false
+                },
+            ]
+        );
+
+        let gates: Vec<_> = er301::Commands::gates_from_mask(0x8001).collect();
+        assert_eq!(gates.len(), 16);
+        assert_eq!(
+            gates[15],
+            er301::Commands::SetGate {
+                port: 15,
+                state: true
+            }
+        );
+        assert_eq!(
+            gates
+                .iter()
+                .filter(|command| matches!(command, er301::Commands::SetGate { state: true, .. }))
+                .count(),
+            2
+        );
+    }
+
+    #[test]
+    fn ansible_checked_constructors_reject_out_of_range_fields() {
+        assert_eq!(
+            ansible::Commands::set_cv(3, -4096),
+            Ok(ansible::Commands::SetCv {
+                port: 3,
+                value: -4096
+            })
+        );
+        assert_eq!(
+            ansible::Commands::set_tr_pulse(4),
+            Err(BuildError::OutOfRange { field: "port" })
+        );
+        assert_eq!(
+            ansible::Commands::load_preset(8),
+            Err(BuildError::OutOfRange { field: "preset" })
+        );
+    }
+
+    #[cfg(feature = "embedded-hal")]
+    #[test]
+    fn send_writes_serialized_command() {
+        use embedded_hal_mock::eh1::i2c::{Mock, Transaction};
+
+        let mut i2c = Mock::new(&[Transaction::write(
+            er301::ADDRESS,
+            vec![0x11, 0x05, 0x20, 0x00],
+        )]);
+        let command = er301::Commands::SetCv {
+            port: 5,
+            value: 8192,
+        };
+        assert_eq!(hal::send(&mut i2c, er301::ADDRESS, &command), Ok(()));
+        i2c.done();
+    }
+
+    #[cfg(feature = "embedded-hal")]
+    #[test]
+    fn send_errors_display_concise_messages() {
+        use embedded_hal::i2c::ErrorKind;
+        use std::error::Error;
+
+        let error: Box<dyn Error> = Box::new(hal::SendError::I2c(ErrorKind::Bus));
+        assert_eq!(error.to_string(), "I2C write failed: Bus");
+        assert_eq!(
+            hal::SendError::<ErrorKind>::from(SerializationError::buffer_too_small(4, 2))
+                .to_string(),
+            "buffer too small: needed 4 bytes, got 2"
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn commands_round_trip_through_json() {
+        let command = just_friends::Commands::PlayNote {
+            output: 1,
+            pitch: 1000,
+            volume: 8000,
+        };
+        let json = serde_json::to_string(&command).unwrap();
+        assert_eq!(
+            json,
+            r#"{"PlayNote":{"output":1,"pitch":1000,"volume":8000}}"#
+        );
+        assert_eq!(
+            serde_json::from_str::<just_friends::Commands>(&json).unwrap(),
+            command
+        );
+    }
+
+    #[cfg(feature = "heapless")]
+    #[test]
+    fn to_vec_holds_exactly_the_serialized_bytes() {
+        let command = telexo::Commands::SetTrPulse { port: 2 };
+        let vec = command
+            .to_vec::<{ telexo::Commands::MAX_LENGTH }>()
+            .unwrap();
+        assert_eq!(vec.len(), command.len());
+        assert_eq!(&vec[..], &[0x14, 0x02]);
+
+        let command = telexi::Commands::SetInputMap {
+            port: 0,
+            top: 1,
+            bottom: -1,
+        };
+        assert_eq!(
+            command.to_vec::<4>(),
+            Err(SerializationError::BufferTooSmall { needed: 6, got: 4 })
+        );
+    }
+
+    #[cfg(feature = "heapless")]
+    #[test]
+    fn write_into_streams_bytes_into_a_sink() {
+        let mut vec = heapless::Vec::<u8, 8>::new();
+        let command = er301::Commands::SetCv {
+            port: 5,
+            value: 8192,
+        };
+        assert_eq!(command.write_into(&mut vec), Ok(4));
+        assert_eq!(
+            telexo::Commands::SetTrPulse { port: 2 }.write_into(&mut vec),
+            Ok(2)
+        );
+        assert_eq!(&vec[..], &[0x11, 0x05, 0x20, 0x00, 0x14, 0x02]);
+        assert_eq!(
+            command.write_into(&mut vec),
+            Err(WriteError::Sink(SinkFull))
+        );
+    }
+
+    #[test]
+    fn slices_are_byte_sinks_that_advance() {
+        let mut buffer = [0u8; 6];
+        let mut sink = &mut buffer[..];
+        let command = Raw::<3>::new(&[0x7F, 0x01, 0x02]).unwrap();
+        assert_eq!(command.write_into(&mut sink), Ok(3));
+        assert_eq!(sink.len(), 3);
+        assert_eq!(
+            just_friends::Commands::SetMode { on: true }.write_into(&mut sink),
+            Ok(2)
+        );
+        #[cfg(feature = "strict")]
+        assert_eq!(
+            er301::Commands::SetCv {
+                port: 200,
+                value: 0
+            }
+            .write_into(&mut sink),
+            Err(WriteError::Serialization(
+                SerializationError::ValueOutOfRange { field: "port" }
+            ))
+        );
+        assert_eq!(buffer, [0x7F, 0x01, 0x02, 0x06, 0x01, 0x00]);
+    }
+
+    #[test]
+    fn errors_display_concise_messages() {
+        use std::error::Error;
+        let error: Box<dyn Error> = Box::new(SerializationError::buffer_too_small(4, 2));
+        assert_eq!(error.to_string(), "buffer too small: needed 4 bytes, got 2");
+        assert_eq!(
+            SerializationError::ValueOutOfRange { field: "port" }.to_string(),
+            "`port` is outside its documented range"
+        );
+        assert_eq!(
+            DeserializationError::UnknownOpcode(0x7F).to_string(),
+            "unknown opcode 0x7F"
+        );
+        assert_eq!(
+            BuildError::OutOfRange { field: "preset" }.to_string(),
+            "`preset` is outside its documented range"
+        );
+        assert_eq!(
+            TransactionError::TooManyCommands { max: 3 }.to_string(),
+            "transaction already holds its maximum of 3 commands"
+        );
+    }
+
+    #[cfg(feature = "heapless")]
+    #[test]
+    fn hex_dumps_separate_bytes_with_spaces() {
+        let mut text = heapless::String::<16>::new();
+        debug::write_hex(&[0x11, 0x05, 0x20, 0x00], &mut text).unwrap();
+        assert_eq!(text, "11 05 20 00");
+
+        text.clear();
+        debug::write_hex(&[], &mut text).unwrap();
+        assert_eq!(text, "");
+
+        let mut short = heapless::String::<4>::new();
+        assert!(debug::write_hex(&[0xAB, 0xCD], &mut short).is_err());
+    }
+
+    #[test]
+    fn seq_concatenates_commands_at_compile_time() {
+        const SEQUENCE: ([u8; 9], [u8; 3]) = seq![
+            ansible::Commands::SetTrPulse { port: 1 },
+            just_friends::Commands::PlayNote {
+                output: 0,
+                pitch: 1638,
+                volume: 8192,
+            },
+            telexo::Commands::Kill,
+        ];
+        assert_eq!(
+            SEQUENCE.0,
+            [0x12, 0x01, 0x08, 0x00, 0x06, 0x66, 0x20, 0x00, 0x70]
+        );
+        assert_eq!(SEQUENCE.1, [2, 6, 1]);
+
+        const EMPTY: ([u8; 0], [u8; 0]) = seq![];
+        assert_eq!(EMPTY, ([], []));
+    }
+
+    #[test]
+    fn cv_conversions_use_teletype_scaling() {
+        assert_eq!(conv::volts_to_cv(10.0), 16384);
+        assert_eq!(conv::volts_to_cv(-5.0), -8192);
+        assert_eq!(conv::volts_to_cv(100.0), i16::MAX);
+        assert_eq!(conv::semitones_to_cv(12.0), 1638);
+        assert_eq!(conv::cv_to_volts(8192), 5.0);
+        assert_eq!(conv::millivolts_to_cv(1000), 1638);
+        assert_eq!(conv::millivolts_to_cv(-10000), -16384);
+        assert_eq!(conv::cv_to_millivolts(1638), 1000);
+    }
+
+    #[test]
+    fn pitches_render_per_device_scale() {
+        use conv::{DeviceScale, Pitch};
+        for scale in [
+            DeviceScale::Er301,
+            DeviceScale::Telexo,
+            DeviceScale::JustFriends,
+        ] {
+            let octave = Pitch::from_semitones(12).for_device(scale);
+            assert_eq!(octave - Pitch::from_semitones(0).for_device(scale), 1638);
+            assert_eq!(Pitch::from_octave(1, 0).for_device(scale), octave);
+        }
+        assert_eq!(
+            Pitch::from_octave(-1, 0).for_device(DeviceScale::Er301),
+            -1638
+        );
+        assert_eq!(Pitch::from_cents(50).for_device(DeviceScale::Telexo), 68);
+        assert_eq!(
+            Pitch::from_semitones(1000).for_device(DeviceScale::Telexo),
+            i16::MAX
+        );
+
+        assert_eq!(conv::div_round_half_even(5, 2), 2);
+        assert_eq!(conv::div_round_half_even(7, 2), 4);
+        assert_eq!(conv::div_round_half_even(-5, 2), -2);
+        assert_eq!(conv::div_round_half_even(-7, 2), -4);
+        assert_eq!(conv::div_round_half_even(8, 3), 3);
+    }
+
+    #[test]
+    fn midi_notes_map_to_device_pitch() {
+        // C0, C4, A4 and C5.
+        assert_eq!(conv::midi_note_to_txo_pitch(12), 1638);
+        assert_eq!(conv::midi_note_to_txo_pitch(60), 8192);
+        assert_eq!(conv::midi_note_to_txo_pitch(69), 9421);
+        assert_eq!(conv::midi_note_to_txo_pitch(72), 9830);
+        assert_eq!(conv::midi_note_to_jf_pitch(12), -6554);
+        assert_eq!(conv::midi_note_to_jf_pitch(60), 0);
+        assert_eq!(conv::midi_note_to_jf_pitch(69), 1229);
+        assert_eq!(conv::midi_note_to_jf_pitch(72), 1638);
+    }
+
+    #[test]
+    fn from_bytes_rejects_malformed_input() {
+        use er301::Commands;
+        assert_eq!(
+            Commands::from_bytes(&[]),
+            Err(DeserializationError::UnexpectedEof)
+        );
+        assert_eq!(
+            Commands::from_bytes(&[0x11, 0x05, 0x20]),
+            Err(DeserializationError::UnexpectedEof)
+        );
+        assert_eq!(
+            Commands::from_bytes(&[0xFF, 0x00]),
+            Err(DeserializationError::UnknownOpcode(0xFF))
+        );
+        assert_eq!(
+            Commands::from_bytes(&[0x00, 0x01, 0x02]),
+            Err(DeserializationError::InvalidValue)
+        );
+    }
+
+    #[cfg(feature = "strict")]
+    #[test]
+    fn to_bytes_rejects_out_of_range_fields() {
+        let mut buffer = [0u8; 8];
+        assert_eq!(
+            er301::Commands::SetCv {
+                port: 200,
+                value: 0
+            }
+            .to_bytes(&mut buffer),
+            Err(SerializationError::ValueOutOfRange { field: "port" })
+        );
+        assert_eq!(
+            ansible::Commands::LoadPreset { preset: 8 }.to_bytes(&mut buffer),
+            Err(SerializationError::ValueOutOfRange { field: "preset" })
+        );
+        assert_eq!(
+            telexo::Commands::SetOscWaveform {
+                port: 0,
+                waveform: 5001
+            }
+            .to_bytes(&mut buffer),
+            Err(SerializationError::ValueOutOfRange { field: "waveform" })
+        );
+        assert_eq!(
+            telexo::Commands::SetCvScale { port: 0, scale: 16 }.to_bytes(&mut buffer),
+            Err(SerializationError::ValueOutOfRange { field: "scale" })
+        );
+        assert_eq!(
+            telexo::Commands::SetOscNote { port: 0, note: 128 }.to_bytes(&mut buffer),
+            Err(SerializationError::ValueOutOfRange { field: "note" })
+        );
+        assert_eq!(
+            telexo::Commands::SetOscWidth {
+                port: 0,
+                width: 101
+            }
+            .to_bytes(&mut buffer),
+            Err(SerializationError::ValueOutOfRange { field: "width" })
+        );
+        assert_eq!(
+            telexo::Commands::SetOscRectify { port: 0, mode: 100 }.to_bytes(&mut buffer),
+            Err(SerializationError::ValueOutOfRange { field: "mode" })
+        );
+        assert_eq!(
+            telexo::Commands::SetOscRectify { port: 0, mode: -4 }.to_bytes(&mut buffer),
+            Err(SerializationError::ValueOutOfRange { field: "mode" })
+        );
+        assert_eq!(
+            disting_ex::Commands::NoteOn {
+                note: 200,
+                velocity: 100
+            }
+            .to_bytes(&mut buffer),
+            Err(SerializationError::ValueOutOfRange { field: "note" })
+        );
+        assert_eq!(
+            disting_ex::Commands::NoteOn {
+                note: 60,
+                velocity: 255
+            }
+            .to_bytes(&mut buffer),
+            Err(SerializationError::ValueOutOfRange { field: "velocity" })
+        );
+        assert_eq!(
+            disting_ex::Commands::NoteOff { note: 128 }.to_bytes(&mut buffer),
+            Err(SerializationError::ValueOutOfRange { field: "note" })
+        );
+        assert_eq!(
+            ansible::Commands::KriaSetStep {
+                track: 1,
+                step: 0,
+                state: 3
+            }
+            .to_bytes(&mut buffer),
+            Err(SerializationError::ValueOutOfRange { field: "state" })
+        );
+        assert!(
+            just_friends::Commands::SetGate {
+                output: 0,
+                state: true
+            }
+            .to_bytes(&mut buffer)
+            .is_ok()
+        );
+    }
+
+    #[test]
+    fn crow_query_responses_decode_index_and_value() {
+        assert_eq!(
+            crow::parse_response(&[0x02, 0xFF, 0x9C]),
+            Ok(crow::QueryResponse {
+                index: 2,
+                value: -100
+            })
+        );
+        assert_eq!(
+            crow::parse_response(&[0x02, 0xFF]),
+            Err(DeserializationError::UnexpectedEof)
+        );
+    }
+
+    #[test]
+    fn ansible_preset_responses_decode_the_preset() {
+        let request = ansible::Commands::RequestCurrentPreset;
+        let mut buffer = [0u8; ansible::Commands::MAX_LENGTH];
+        assert_eq!(request.to_bytes(&mut buffer), Ok(&[0x22][..]));
+        assert_eq!(ansible::parse_preset_response(&[5]), Ok(5));
+        assert_eq!(
+            ansible::parse_preset_response(&[8]),
+            Err(DeserializationError::InvalidValue)
+        );
+        assert_eq!(
+            ansible::parse_preset_response(&[]),
+            Err(DeserializationError::UnexpectedEof)
+        );
+    }
+
+    #[test]
+    fn from_bytes_reports_consumed_length() {
+        let bytes = [0x11, 0x05, 0x20, 0x00, 0x12, 0x05];
+        assert_eq!(
+            er301::Commands::from_bytes(&bytes),
+            Ok((
+                er301::Commands::SetCv {
+                    port: 5,
+                    value: 8192
+                },
+                4
+            ))
+        );
+    }
+}
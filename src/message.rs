@@ -0,0 +1,45 @@
+//! An owned, serialized command that needs no caller-sized buffer.
+
+use crate::{Command, MAX_MESSAGE_LENGTH, SerializationError};
+
+/// A serialized command held in a stack buffer that fits any supported device command.
+///
+/// This removes device-specific buffer sizing from call sites:
+///
+/// ```
+/// use mii::{Message, devices::er301};
+///
+/// let command = er301::Commands::SetCv { port: 5, value: 8192 };
+/// let message = Message::new(&command).unwrap();
+/// assert_eq!(message.as_slice(), &[0x11, 0x05, 0x20, 0x00]);
+/// // i2c.write(er301::ADDRESS, message.as_slice());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Message {
+    buffer: [u8; MAX_MESSAGE_LENGTH],
+    len: usize,
+}
+
+impl Message {
+    /// Serializes `command` into a new message.
+    ///
+    /// Commands longer than [`MAX_MESSAGE_LENGTH`] are rejected at compile time.
+    pub fn new<C: Command>(command: &C) -> Result<Self, SerializationError> {
+        const { assert!(C::MAX_LENGTH <= MAX_MESSAGE_LENGTH) };
+        let mut buffer = [0u8; MAX_MESSAGE_LENGTH];
+        let len = command.to_bytes(&mut buffer)?.len();
+        Ok(Self { buffer, len })
+    }
+
+    /// Returns the serialized bytes.
+    pub fn as_slice(&self) -> &[u8] {
+        &self.buffer[..self.len]
+    }
+}
+
+impl AsRef<[u8]> for Message {
+    fn as_ref(&self) -> &[u8] {
+        self.as_slice()
+    }
+}
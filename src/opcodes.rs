@@ -0,0 +1,246 @@
+//! The opcode of every command variant, per device.
+//!
+//! Each table is the reviewed list of which opcode a variant owns. The checks below fail
+//! when two variants of a device share an opcode, or when a command's `opcode` or its
+//! parser disagrees with the table. No supported device reuses an opcode and tells the
+//! commands apart by a later byte, so every opcode must be unique within its device.
+
+use core::fmt::Debug;
+
+use crate::Parse;
+use crate::devices::*;
+
+const ANSIBLE: &[(&str, u8)] = &[
+    ("SetCv", 0x01),
+    ("SetCvSlew", 0x02),
+    ("SetCvFromFader", 0x06),
+    ("SetTrState", 0x10),
+    ("SetTrToggle", 0x11),
+    ("SetTrPulse", 0x12),
+    ("SetTrPulseDuration", 0x13),
+    ("SetTrPolarity", 0x14),
+    ("LoadPreset", 0x20),
+    ("SavePreset", 0x21),
+    ("RequestCurrentPreset", 0x22),
+    ("KriaSetStep", 0x30),
+    ("KriaSetTempo", 0x31),
+    ("KriaSelectPattern", 0x32),
+    ("KriaMute", 0x33),
+    ("KriaTranspose", 0x34),
+    ("MeadowphysicsStart", 0x40),
+    ("MeadowphysicsStop", 0x41),
+    ("MeadowphysicsSetSpeed", 0x42),
+    ("MeadowphysicsReset", 0x43),
+    ("LevelsSetValue", 0x50),
+    ("LevelsReset", 0x51),
+    ("CyclesSetFreq", 0x60),
+    ("CyclesReset", 0x61),
+];
+
+const CROW: &[(&str, u8)] = &[("SetOutput", 0x01), ("CallFunction", 0x02), ("Query", 0x03)];
+
+const DISTING_EX: &[(&str, u8)] = &[
+    ("SelectAlgorithm", 0x44),
+    ("SetParameter", 0x46),
+    ("PlaySample", 0x4D),
+    ("SetZModeParameter", 0x4A),
+    ("LoadPreset", 0x40),
+    ("SavePreset", 0x41),
+    ("SelectSdFile", 0x4B),
+    ("NoteOn", 0x55),
+    ("NoteOff", 0x56),
+];
+
+const ER301: &[(&str, u8)] = &[
+    ("SetGate", 0x00),
+    ("SetTrToggle", 0x13),
+    ("SetTrPulse", 0x14),
+    ("SetTrTime", 0x15),
+    ("SetTrPolarity", 0x16),
+    ("SetCv", 0x11),
+    ("SetCvImmediate", 0x10),
+    ("SetCvSlew", 0x12),
+];
+
+const FADERBANK: &[(&str, u8)] = &[("SetCvFromFader", 0x06)];
+
+const FH2: &[(&str, u8)] = &[
+    ("SetGate", 0x00),
+    ("SetCv", 0x11),
+    ("SetClockDivision", 0x20),
+    ("SetClockBpm", 0x21),
+    ("Start", 0x22),
+    ("Stop", 0x23),
+    ("Continue", 0x24),
+];
+
+const JUST_FRIENDS: &[(&str, u8)] = &[
+    ("SetGate", 0x01),
+    ("PlayNote", 0x08),
+    ("SetVoice", 0x09),
+    ("SetIntone", 0x15),
+    ("SetMode", 0x06),
+    ("SetRun", 0x03),
+    ("SetRunMode", 0x02),
+    ("SetTranspose", 0x04),
+    ("SetRamp", 0x11),
+    ("SetCurve", 0x12),
+    ("SetFm", 0x13),
+    ("SetTime", 0x14),
+    ("SetQuantize", 0x0C),
+    ("Tune", 0x0B),
+    ("SetGodMode", 0x0A),
+];
+
+const TELETYPE: &[(&str, u8)] = &[
+    ("SetScript", 0x00),
+    ("SetTrigger", 0x01),
+    ("SetPattern", 0x02),
+];
+
+const TELEXI: &[(&str, u8)] = &[
+    ("SetInputScale", 0x03),
+    ("SetInputMap", 0x04),
+    ("SetParamQuantize", 0x11),
+    ("SetParamScale", 0x13),
+    ("SetParamMap", 0x14),
+    ("Store", 0x20),
+    ("Reset", 0x21),
+    ("RequestInput", 0x00),
+    ("RequestParam", 0x10),
+];
+
+const TELEXO: &[(&str, u8)] = &[
+    ("SetGate", 0x00),
+    ("SetTrToggle", 0x13),
+    ("SetTrPulse", 0x14),
+    ("SetTrTime", 0x15),
+    ("SetTrPolarity", 0x16),
+    ("SetCv", 0x11),
+    ("SetCvImmediate", 0x10),
+    ("SetCvSlew", 0x12),
+    ("SetCvOffset", 0x17),
+    ("SetCvQuantizedNote", 0x18),
+    ("SetCvScale", 0x19),
+    ("SetOscPitch", 0x41),
+    ("SetOscNote", 0x42),
+    ("OscSync", 0x43),
+    ("SetOscPhase", 0x44),
+    ("SetOscLfoMode", 0x45),
+    ("SetOscLfoRate", 0x46),
+    ("SetOscFreq", 0x48),
+    ("SetOscSlew", 0x49),
+    ("SetOscWaveform", 0x4A),
+    ("SetOscWidth", 0x4B),
+    ("SetOscRectify", 0x4C),
+    ("SetOscCenter", 0x4D),
+    ("SetEnvelopeMode", 0x60),
+    ("SetEnvelopeAttack", 0x61),
+    ("SetEnvelopeDecay", 0x62),
+    ("SetEnvelopeEor", 0x63),
+    ("SetEnvelopeEoc", 0x64),
+    ("SetEnvelopeState", 0x6D),
+    ("SetMetroTime", 0x30),
+    ("SetMetroActive", 0x31),
+    ("SetMetroBpm", 0x32),
+    ("Kill", 0x70),
+    ("Reset", 0x71),
+];
+
+const W: &[(&str, u8)] = &[
+    ("Play", 0x01),
+    ("Record", 0x02),
+    ("Loop", 0x03),
+    ("SetSpeed", 0x04),
+    ("SetLoopStart", 0x05),
+    ("SetLoopEnd", 0x06),
+    ("CueTo", 0x07),
+    ("RecordArm", 0x08),
+    ("SetRecordThreshold", 0x09),
+    ("Erase", 0x0A),
+];
+
+/// Asserts that the opcodes in `table` are unique, and that every opcode the device
+/// parses belongs to the variant the table assigns it to.
+fn check_opcodes<C: Parse + Debug>(table: &[(&str, u8)], opcode: fn(&C) -> u8) {
+    for (i, (name, code)) in table.iter().enumerate() {
+        for (other, other_code) in &table[..i] {
+            assert_ne!(code, other_code, "{name} reuses the opcode of {other}");
+        }
+    }
+
+    for code in 0..=u8::MAX {
+        let mut bytes = [0u8; 16];
+        bytes[0] = code;
+        let Ok((command, _)) = C::from_bytes(&bytes) else {
+            assert!(
+                table.iter().all(|&(_, owned)| owned != code),
+                "opcode {code:#04X} is in the table but does not parse"
+            );
+            continue;
+        };
+        let debug = format!("{command:?}");
+        let name = debug.split(|c: char| !c.is_alphanumeric()).next().unwrap();
+        assert_eq!(
+            table.iter().find(|&&(variant, _)| variant == name),
+            Some(&(name, code)),
+            "{name} parses from {code:#04X}"
+        );
+        assert_eq!(opcode(&command), code, "{name}");
+    }
+}
+
+#[test]
+fn ansible() {
+    check_opcodes(ANSIBLE, ansible::Commands::opcode);
+}
+
+#[test]
+fn crow() {
+    check_opcodes(CROW, crow::Commands::opcode);
+}
+
+#[test]
+fn disting_ex() {
+    check_opcodes(DISTING_EX, disting_ex::Commands::opcode);
+}
+
+#[test]
+fn er301() {
+    check_opcodes(ER301, er301::Commands::opcode);
+}
+
+#[test]
+fn faderbank() {
+    check_opcodes(FADERBANK, faderbank::Commands::opcode);
+}
+
+#[test]
+fn fh2() {
+    check_opcodes(FH2, fh2::Commands::opcode);
+}
+
+#[test]
+fn just_friends() {
+    check_opcodes(JUST_FRIENDS, just_friends::Commands::opcode);
+}
+
+#[test]
+fn teletype() {
+    check_opcodes(TELETYPE, teletype::Commands::opcode);
+}
+
+#[test]
+fn telexi() {
+    check_opcodes(TELEXI, telexi::Commands::opcode);
+}
+
+#[test]
+fn telexo() {
+    check_opcodes(TELEXO, telexo::Commands::opcode);
+}
+
+#[test]
+fn w() {
+    check_opcodes(W, w::Commands::opcode);
+}
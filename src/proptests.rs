@@ -0,0 +1,433 @@
+//! Property tests for the codec: every valid command must survive a round trip through
+//! its bytes, and parsing arbitrary bytes must never panic.
+//!
+//! The [`Arbitrary`] impls only generate fields within the ranges documented on each
+//! variant, so the commands also pass validation.
+
+use core::fmt::Debug;
+
+use proptest::collection::vec;
+use proptest::prelude::*;
+use proptest::test_runner::TestCaseError;
+
+use crate::devices::*;
+use crate::{Command, Parse};
+
+fn check_round_trip<C: Command + Parse + PartialEq + Debug>(
+    command: C,
+) -> Result<(), TestCaseError> {
+    let mut buffer = [0u8; 16];
+    let bytes = command
+        .to_bytes(&mut buffer)
+        .map_err(|error| TestCaseError::fail(format!("{command:?}: {error:?}")))?;
+    prop_assert_eq!(bytes.len(), command.len());
+    prop_assert_eq!(C::from_bytes(bytes), Ok((command, bytes.len())));
+    Ok(())
+}
+
+fn check_parse<C: Command + Parse>(bytes: &[u8]) -> Result<(), TestCaseError> {
+    if let Ok((command, consumed)) = C::from_bytes(bytes) {
+        prop_assert!(consumed <= bytes.len());
+        prop_assert_eq!(consumed, command.len());
+    }
+    Ok(())
+}
+
+impl Arbitrary for ansible::Commands {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        use ansible::Commands::*;
+        prop_oneof![
+            (0..=3u8, any::<i16>()).prop_map(|(port, value)| SetCv { port, value }),
+            (0..=3u8, any::<u16>()).prop_map(|(port, ms)| SetCvSlew { port, ms }),
+            (any::<u8>(), any::<u16>())
+                .prop_map(|(device_port, value)| SetCvFromFader { device_port, value }),
+            (0..=3u8, any::<bool>()).prop_map(|(port, state)| SetTrState { port, state }),
+            (0..=3u8).prop_map(|port| SetTrToggle { port }),
+            (0..=3u8).prop_map(|port| SetTrPulse { port }),
+            (0..=3u8, any::<u16>()).prop_map(|(port, ms)| SetTrPulseDuration { port, ms }),
+            (0..=3u8, any::<bool>()).prop_map(|(port, polarity)| SetTrPolarity { port, polarity }),
+            (0..=7u8).prop_map(|preset| LoadPreset { preset }),
+            (0..=7u8).prop_map(|preset| SavePreset { preset }),
+            (1..=4u8, 0..=15u8, 0..=2u8).prop_map(|(track, step, state)| KriaSetStep {
+                track,
+                step,
+                state
+            }),
+            any::<u16>().prop_map(|bpm| KriaSetTempo { bpm }),
+            (0..=15u8).prop_map(|pattern| KriaSelectPattern { pattern }),
+            (1..=4u8, any::<bool>()).prop_map(|(track, state)| KriaMute { track, state }),
+            (1..=4u8, any::<i8>())
+                .prop_map(|(track, semitones)| KriaTranspose { track, semitones }),
+            Just(MeadowphysicsStart),
+            Just(MeadowphysicsStop),
+            (0..=7u8, any::<u16>()).prop_map(|(row, speed)| MeadowphysicsSetSpeed { row, speed }),
+            (0..=7u8).prop_map(|row| MeadowphysicsReset { row }),
+            (1..=4u8, any::<i16>()).prop_map(|(track, value)| LevelsSetValue { track, value }),
+            Just(LevelsReset),
+            (0..=3u8, any::<u16>()).prop_map(|(channel, freq)| CyclesSetFreq { channel, freq }),
+            Just(CyclesReset),
+            Just(RequestCurrentPreset),
+        ]
+        .boxed()
+    }
+}
+
+impl Arbitrary for crow::Commands {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        use crow::Commands::*;
+        prop_oneof![
+            (1..=4u8, any::<i16>()).prop_map(|(channel, value)| SetOutput { channel, value }),
+            (1..=4u8, any::<i16>()).prop_map(|(index, arg)| CallFunction { index, arg }),
+            (0..=3u8).prop_map(|index| Query { index }),
+        ]
+        .boxed()
+    }
+}
+
+impl Arbitrary for disting_ex::Commands {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        use disting_ex::Commands::*;
+        prop_oneof![
+            any::<u8>().prop_map(|index| SelectAlgorithm { index }),
+            (any::<u8>(), any::<i16>()).prop_map(|(param, value)| SetParameter { param, value }),
+            any::<u8>().prop_map(|slot| PlaySample { slot }),
+            (any::<u8>(), any::<i16>())
+                .prop_map(|(param, value)| SetZModeParameter { param, value }),
+            any::<u8>().prop_map(|index| LoadPreset { index }),
+            any::<u8>().prop_map(|index| SavePreset { index }),
+            (any::<u8>(), any::<u8>()).prop_map(|(folder, file)| SelectSdFile { folder, file }),
+            (0..=127u8, 0..=127u8).prop_map(|(note, velocity)| NoteOn { note, velocity }),
+            (0..=127u8).prop_map(|note| NoteOff { note }),
+        ]
+        .boxed()
+    }
+}
+
+impl Arbitrary for er301::Commands {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        use er301::Commands::*;
+        prop_oneof![
+            (0..=99u8, any::<bool>()).prop_map(|(port, state)| SetGate { port, state }),
+            (0..=99u8).prop_map(|port| SetTrToggle { port }),
+            (0..=99u8).prop_map(|port| SetTrPulse { port }),
+            (0..=99u8, any::<u16>()).prop_map(|(port, ms)| SetTrTime { port, ms }),
+            (0..=99u8, any::<bool>()).prop_map(|(port, polarity)| SetTrPolarity { port, polarity }),
+            (0..=99u8, any::<i16>()).prop_map(|(port, value)| SetCv { port, value }),
+            (0..=99u8, any::<i16>()).prop_map(|(port, value)| SetCvImmediate { port, value }),
+            (0..=99u8, any::<u16>()).prop_map(|(port, ms)| SetCvSlew { port, ms }),
+        ]
+        .boxed()
+    }
+}
+
+impl Arbitrary for faderbank::Commands {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        use faderbank::Commands::*;
+        prop_oneof![
+            (any::<u8>(), any::<u16>())
+                .prop_map(|(device_port, value)| SetCvFromFader { device_port, value }),
+        ]
+        .boxed()
+    }
+}
+
+impl Arbitrary for fh2::Commands {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        use fh2::Commands::*;
+        prop_oneof![
+            (0..=63u8, any::<bool>()).prop_map(|(channel, state)| SetGate { channel, state }),
+            (0..=63u8, any::<i16>()).prop_map(|(channel, value)| SetCv { channel, value }),
+            any::<u8>().prop_map(|value| SetClockDivision { value }),
+            any::<u16>().prop_map(|bpm| SetClockBpm { bpm }),
+            Just(Start),
+            Just(Stop),
+            Just(Continue),
+        ]
+        .boxed()
+    }
+}
+
+impl Arbitrary for just_friends::Commands {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        use just_friends::Commands::*;
+        prop_oneof![
+            (0..=6u8, any::<bool>()).prop_map(|(output, state)| SetGate { output, state }),
+            (0..=6u8, any::<i16>(), any::<i16>()).prop_map(|(output, pitch, volume)| PlayNote {
+                output,
+                pitch,
+                volume
+            }),
+            (0..=6u8, any::<i16>(), any::<i16>()).prop_map(|(channel, pitch, level)| SetVoice {
+                channel,
+                pitch,
+                level
+            }),
+            (0..=6u8, any::<i16>()).prop_map(|(channel, ratio)| SetIntone { channel, ratio }),
+            any::<bool>().prop_map(|on| SetMode { on }),
+            any::<i16>().prop_map(|value| SetRun { value }),
+            any::<i16>().prop_map(|value| SetTranspose { value }),
+            any::<i16>().prop_map(|value| SetRamp { value }),
+            any::<i16>().prop_map(|value| SetCurve { value }),
+            any::<i16>().prop_map(|value| SetFm { value }),
+            any::<bool>().prop_map(|on| SetQuantize { on }),
+            (0..=6u8, any::<i16>()).prop_map(|(channel, value)| Tune { channel, value }),
+            (0..=1u8).prop_map(|mode| SetRunMode { mode }),
+            any::<bool>().prop_map(|on| SetGodMode { on }),
+            (0..=6u8, any::<i16>()).prop_map(|(channel, value)| SetTime { channel, value }),
+        ]
+        .boxed()
+    }
+}
+
+impl Arbitrary for teletype::Commands {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        use teletype::Commands::*;
+        prop_oneof![
+            (1..=10u8).prop_map(|script| SetScript { script }),
+            (1..=8u8).prop_map(|script| SetTrigger { script }),
+            (0..=63u8, any::<i16>()).prop_map(|(index, value)| SetPattern { index, value }),
+        ]
+        .boxed()
+    }
+}
+
+impl Arbitrary for telexi::Commands {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        use telexi::Commands::*;
+        prop_oneof![
+            (0..=3u8, any::<i16>(), any::<i16>()).prop_map(|(port, min, max)| SetInputScale {
+                port,
+                min,
+                max
+            }),
+            (0..=3u8, any::<i16>(), any::<i16>()).prop_map(|(port, top, bottom)| SetInputMap {
+                port,
+                top,
+                bottom
+            }),
+            (0..=3u8, any::<u8>()).prop_map(|(port, scale)| SetParamQuantize { port, scale }),
+            Just(Store),
+            Just(Reset),
+            (0..=3u8).prop_map(|port| RequestInput { port }),
+            (0..=3u8).prop_map(|port| RequestParam { port }),
+            (0..=3u8, any::<i16>(), any::<i16>()).prop_map(|(port, min, max)| SetParamScale {
+                port,
+                min,
+                max
+            }),
+            (0..=3u8, any::<i16>(), any::<i16>()).prop_map(|(port, top, bottom)| SetParamMap {
+                port,
+                top,
+                bottom
+            }),
+        ]
+        .boxed()
+    }
+}
+
+impl Arbitrary for telexo::Commands {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        use telexo::Commands::*;
+        prop_oneof![
+            (0..=3u8, any::<bool>()).prop_map(|(port, state)| SetGate { port, state }),
+            (0..=3u8).prop_map(|port| SetTrToggle { port }),
+            (0..=3u8).prop_map(|port| SetTrPulse { port }),
+            (0..=3u8, any::<u16>()).prop_map(|(port, ms)| SetTrTime { port, ms }),
+            (0..=3u8, any::<bool>()).prop_map(|(port, polarity)| SetTrPolarity { port, polarity }),
+            (0..=3u8, any::<i16>()).prop_map(|(port, value)| SetCv { port, value }),
+            (0..=3u8, any::<u16>()).prop_map(|(port, ms)| SetCvSlew { port, ms }),
+            (0..=3u8, any::<i16>()).prop_map(|(port, value)| SetCvOffset { port, value }),
+            (0..=3u8, any::<u8>()).prop_map(|(port, note)| SetCvQuantizedNote { port, note }),
+            (0..=3u8, any::<i16>()).prop_map(|(port, pitch)| SetOscPitch { port, pitch }),
+            (0..=3u8, 0..=127u8).prop_map(|(port, note)| SetOscNote { port, note }),
+            (0..=3u8).prop_map(|port| OscSync { port }),
+            (0..=3u8, any::<u16>()).prop_map(|(port, phase)| SetOscPhase { port, phase }),
+            (0..=3u8, any::<bool>()).prop_map(|(port, enabled)| SetOscLfoMode { port, enabled }),
+            (0..=3u8, any::<i16>()).prop_map(|(port, rate)| SetOscLfoRate { port, rate }),
+            (0..=3u8, any::<u16>()).prop_map(|(port, freq)| SetOscFreq { port, freq }),
+            (0..=3u8, any::<u16>()).prop_map(|(port, ms)| SetOscSlew { port, ms }),
+            (0..=3u8, 0..=5000u16).prop_map(|(port, waveform)| SetOscWaveform { port, waveform }),
+            (0..=3u8, 0..=100u16).prop_map(|(port, width)| SetOscWidth { port, width }),
+            (0..=3u8, -3..=3i8).prop_map(|(port, mode)| SetOscRectify { port, mode }),
+            (0..=3u8, any::<bool>()).prop_map(|(port, enabled)| SetEnvelopeMode { port, enabled }),
+            (0..=3u8, any::<u16>()).prop_map(|(port, ms)| SetEnvelopeAttack { port, ms }),
+            (0..=3u8, any::<u16>()).prop_map(|(port, ms)| SetEnvelopeDecay { port, ms }),
+            (0..=3u8, any::<u8>()).prop_map(|(port, dest)| SetEnvelopeEor { port, dest }),
+            (0..=3u8, any::<u8>()).prop_map(|(port, dest)| SetEnvelopeEoc { port, dest }),
+            (0..=3u8, any::<bool>()).prop_map(|(port, on)| SetEnvelopeState { port, on }),
+            any::<u16>().prop_map(|ms| SetMetroTime { ms }),
+            any::<bool>().prop_map(|state| SetMetroActive { state }),
+            any::<u16>().prop_map(|bpm| SetMetroBpm { bpm }),
+            (0..=3u8, any::<i16>()).prop_map(|(port, value)| SetCvImmediate { port, value }),
+            (0..=3u8, any::<i16>()).prop_map(|(port, value)| SetOscCenter { port, value }),
+            (0..=3u8, 0..=15u8).prop_map(|(port, scale)| SetCvScale { port, scale }),
+            Just(Kill),
+            Just(Reset),
+        ]
+        .boxed()
+    }
+}
+
+impl Arbitrary for w::Commands {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        use w::Commands::*;
+        prop_oneof![
+            any::<i16>().prop_map(|speed| Play { speed }),
+            any::<bool>().prop_map(|state| Record { state }),
+            (any::<i16>(), any::<i16>()).prop_map(|(start, end)| Loop { start, end }),
+            any::<i16>().prop_map(|value| SetSpeed { value }),
+            any::<i16>().prop_map(|position| SetLoopStart { position }),
+            any::<i16>().prop_map(|position| SetLoopEnd { position }),
+            any::<i16>().prop_map(|position| CueTo { position }),
+            any::<bool>().prop_map(|state| RecordArm { state }),
+            any::<i16>().prop_map(|value| SetRecordThreshold { value }),
+            Just(Erase),
+        ]
+        .boxed()
+    }
+}
+
+proptest! {
+    #[test]
+    fn ansible_round_trips(command: ansible::Commands) {
+        check_round_trip(command)?;
+    }
+
+    #[test]
+    fn ansible_parses_any_bytes(bytes in vec(any::<u8>(), 0..16)) {
+        check_parse::<ansible::Commands>(&bytes)?;
+    }
+
+    #[test]
+    fn crow_round_trips(command: crow::Commands) {
+        check_round_trip(command)?;
+    }
+
+    #[test]
+    fn crow_parses_any_bytes(bytes in vec(any::<u8>(), 0..16)) {
+        check_parse::<crow::Commands>(&bytes)?;
+    }
+
+    #[test]
+    fn disting_ex_round_trips(command: disting_ex::Commands) {
+        check_round_trip(command)?;
+    }
+
+    #[test]
+    fn disting_ex_parses_any_bytes(bytes in vec(any::<u8>(), 0..16)) {
+        check_parse::<disting_ex::Commands>(&bytes)?;
+    }
+
+    #[test]
+    fn er301_round_trips(command: er301::Commands) {
+        check_round_trip(command)?;
+    }
+
+    #[test]
+    fn er301_parses_any_bytes(bytes in vec(any::<u8>(), 0..16)) {
+        check_parse::<er301::Commands>(&bytes)?;
+    }
+
+    #[test]
+    fn faderbank_round_trips(command: faderbank::Commands) {
+        check_round_trip(command)?;
+    }
+
+    #[test]
+    fn faderbank_parses_any_bytes(bytes in vec(any::<u8>(), 0..16)) {
+        check_parse::<faderbank::Commands>(&bytes)?;
+    }
+
+    #[test]
+    fn fh2_round_trips(command: fh2::Commands) {
+        check_round_trip(command)?;
+    }
+
+    #[test]
+    fn fh2_parses_any_bytes(bytes in vec(any::<u8>(), 0..16)) {
+        check_parse::<fh2::Commands>(&bytes)?;
+    }
+
+    #[test]
+    fn just_friends_round_trips(command: just_friends::Commands) {
+        check_round_trip(command)?;
+    }
+
+    #[test]
+    fn just_friends_parses_any_bytes(bytes in vec(any::<u8>(), 0..16)) {
+        check_parse::<just_friends::Commands>(&bytes)?;
+    }
+
+    #[test]
+    fn teletype_round_trips(command: teletype::Commands) {
+        check_round_trip(command)?;
+    }
+
+    #[test]
+    fn teletype_parses_any_bytes(bytes in vec(any::<u8>(), 0..16)) {
+        check_parse::<teletype::Commands>(&bytes)?;
+    }
+
+    #[test]
+    fn telexi_round_trips(command: telexi::Commands) {
+        check_round_trip(command)?;
+    }
+
+    #[test]
+    fn telexi_parses_any_bytes(bytes in vec(any::<u8>(), 0..16)) {
+        check_parse::<telexi::Commands>(&bytes)?;
+    }
+
+    #[test]
+    fn telexo_round_trips(command: telexo::Commands) {
+        check_round_trip(command)?;
+    }
+
+    #[test]
+    fn telexo_parses_any_bytes(bytes in vec(any::<u8>(), 0..16)) {
+        check_parse::<telexo::Commands>(&bytes)?;
+    }
+
+    #[test]
+    fn w_round_trips(command: w::Commands) {
+        check_round_trip(command)?;
+    }
+
+    #[test]
+    fn w_parses_any_bytes(bytes in vec(any::<u8>(), 0..16)) {
+        check_parse::<w::Commands>(&bytes)?;
+    }
+}
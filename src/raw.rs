@@ -0,0 +1,73 @@
+//! An escape hatch for II messages this crate does not model yet.
+
+use crate::{ByteSink, Command, SerializationError, WriteError};
+
+/// A command made of caller-supplied bytes, written verbatim, e.g. for an op added in a
+/// newer firmware. It holds up to `N` bytes, including the opcode.
+///
+/// Raw commands bypass all validation by design: the bytes are neither checked against
+/// any device's documented ranges nor required to start with a known opcode.
+///
+/// ```
+/// use mii::{Command, Raw};
+///
+/// let command = Raw::<4>::new(&[0x7F, 0x01, 0x02])?;
+/// let mut buffer = [0u8; 4];
+/// assert_eq!(command.to_bytes(&mut buffer)?, &[0x7F, 0x01, 0x02]);
+/// # Ok::<(), mii::SerializationError>(())
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Raw<const N: usize> {
+    data: [u8; N],
+    len: usize,
+}
+
+impl<const N: usize> Raw<N> {
+    /// Copies `bytes` into a new raw command.
+    ///
+    /// Returns `SerializationError::BufferTooSmall` if `bytes` is longer than `N`.
+    pub const fn new(bytes: &[u8]) -> Result<Self, SerializationError> {
+        if bytes.len() > N {
+            return Err(SerializationError::buffer_too_small(bytes.len(), N));
+        }
+        let mut data = [0u8; N];
+        let mut i = 0;
+        while i < bytes.len() {
+            data[i] = bytes[i];
+            i += 1;
+        }
+        Ok(Self {
+            data,
+            len: bytes.len(),
+        })
+    }
+
+    /// Returns the bytes of the command.
+    pub fn as_slice(&self) -> &[u8] {
+        &self.data[..self.len]
+    }
+}
+
+impl<const N: usize> Command for Raw<N> {
+    const MAX_LENGTH: usize = N;
+
+    fn to_bytes<'a>(&self, buffer: &'a mut [u8]) -> Result<&'a [u8], SerializationError> {
+        if buffer.len() < self.len {
+            return Err(SerializationError::buffer_too_small(self.len, buffer.len()));
+        }
+        buffer[..self.len].copy_from_slice(self.as_slice());
+        Ok(&buffer[..self.len])
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn write_into<S: ByteSink>(&self, sink: &mut S) -> Result<usize, WriteError<S::Error>> {
+        for &byte in self.as_slice() {
+            sink.push(byte).map_err(WriteError::Sink)?;
+        }
+        Ok(self.len)
+    }
+}
@@ -0,0 +1,58 @@
+//! Byte-at-a-time output for streaming transports.
+
+use crate::SerializationError;
+
+/// A destination that accepts serialized bytes one at a time, such as a ring buffer, a
+/// DMA FIFO or a UART-to-II bridge.
+///
+/// [`Command::write_into`](crate::Command::write_into) writes into any sink, so callers
+/// need no pre-sized slice. `&mut [u8]` is a sink that fills the slice from the start
+/// and advances past each written byte, like `std::io::Write` does for slices.
+pub trait ByteSink {
+    /// The error returned when the sink cannot take another byte.
+    type Error;
+
+    /// Appends one byte to the sink.
+    fn push(&mut self, byte: u8) -> Result<(), Self::Error>;
+}
+
+/// The error returned by the built-in sinks once they are full.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct SinkFull;
+
+impl ByteSink for &mut [u8] {
+    type Error = SinkFull;
+
+    fn push(&mut self, byte: u8) -> Result<(), Self::Error> {
+        let (first, rest) = core::mem::take(self).split_first_mut().ok_or(SinkFull)?;
+        *first = byte;
+        *self = rest;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "heapless")]
+impl<const N: usize> ByteSink for heapless::Vec<u8, N> {
+    type Error = SinkFull;
+
+    fn push(&mut self, byte: u8) -> Result<(), Self::Error> {
+        heapless::Vec::push(self, byte).map_err(|_| SinkFull)
+    }
+}
+
+/// Represents errors that can occur while writing a command into a [`ByteSink`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum WriteError<E> {
+    /// The command could not be serialized.
+    Serialization(SerializationError),
+    /// The sink rejected a byte.
+    Sink(E),
+}
+
+impl<E> From<SerializationError> for WriteError<E> {
+    fn from(error: SerializationError) -> Self {
+        Self::Serialization(error)
+    }
+}
@@ -0,0 +1,27 @@
+//! Commands formatted as Teletype script text.
+
+/// A command formatted as the equivalent Teletype op, ready to paste into a script.
+///
+/// It is returned by each device's `Commands::teletype_op`, which returns `None` for
+/// commands that have no Teletype op. Unlike the `Display` output of the commands, it
+/// uses Teletype's own op names and numbering, e.g. Teletype counts ER-301 ports from 1:
+///
+/// ```
+/// use mii::devices::er301;
+///
+/// let command = er301::Commands::SetCv { port: 5, value: 8192 };
+/// assert_eq!(command.to_string(), "ER301.CV 5 8192");
+/// assert_eq!(command.teletype_op().unwrap().to_string(), "SC.CV 6 8192");
+/// ```
+///
+/// Write it into a `heapless::String` or any other `core::fmt::Write` to stay `no_std`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct TeletypeOp<C>(pub(crate) C);
+
+impl<C> TeletypeOp<C> {
+    /// Returns the command the op was built from.
+    pub fn command(&self) -> &C {
+        &self.0
+    }
+}
@@ -0,0 +1,123 @@
+//! Groups of commands that belong together and should be sent without interruption.
+
+use core::fmt;
+
+use crate::{Command, SerializationError};
+
+/// A builder that serializes a group of related commands into one contiguous buffer.
+///
+/// Unlike [`serialize_all`](crate::serialize_all), a transaction remembers where each
+/// command starts and ends, so a driver can issue them as back-to-back writes. It holds
+/// the boundaries of up to `N` commands.
+///
+/// A glitch-free CV jump disables the slew, sets the CV and restores the slew:
+///
+/// ```
+/// use mii::{Transaction, devices::er301::Commands};
+///
+/// let mut buffer = [0u8; 12];
+/// let transaction = Transaction::<3>::new(&mut buffer)
+///     .push(&Commands::SetCvSlew { port: 0, ms: 0 })?
+///     .push(&Commands::SetCv { port: 0, value: 8192 })?
+///     .push(&Commands::SetCvSlew { port: 0, ms: 50 })?;
+///
+/// assert_eq!(transaction.boundaries(), &[(0, 4), (4, 4), (8, 4)]);
+/// for frame in transaction.frames() {
+///     // i2c.write(er301::ADDRESS, frame);
+///     assert_eq!(frame.len(), 4);
+/// }
+/// # Ok::<(), mii::TransactionError>(())
+/// ```
+#[derive(Debug)]
+pub struct Transaction<'a, const N: usize = 8> {
+    buffer: &'a mut [u8],
+    boundaries: [(usize, usize); N],
+    count: usize,
+    len: usize,
+}
+
+impl<'a, const N: usize> Transaction<'a, N> {
+    /// Creates an empty transaction that serializes into `buffer`.
+    pub fn new(buffer: &'a mut [u8]) -> Self {
+        Self {
+            buffer,
+            boundaries: [(0, 0); N],
+            count: 0,
+            len: 0,
+        }
+    }
+
+    /// Appends a command to the transaction.
+    ///
+    /// Returns `TransactionError::TooManyCommands` if the transaction already holds `N`
+    /// commands, and `SerializationError::BufferTooSmall` if the command does not fit in
+    /// the remaining buffer, with `needed` and `got` counting the whole buffer.
+    pub fn push<C: Command>(mut self, command: &C) -> Result<Self, TransactionError> {
+        if self.count == N {
+            return Err(TransactionError::TooManyCommands { max: N });
+        }
+        let written = match command.to_bytes(&mut self.buffer[self.len..]) {
+            Ok(bytes) => bytes.len(),
+            Err(SerializationError::BufferTooSmall { .. }) => {
+                let needed = self.len + command.len();
+                return Err(SerializationError::buffer_too_small(needed, self.buffer.len()).into());
+            }
+            Err(error) => return Err(error.into()),
+        };
+        self.boundaries[self.count] = (self.len, written);
+        self.count += 1;
+        self.len += written;
+        Ok(self)
+    }
+
+    /// Returns the bytes of all commands in the transaction.
+    pub fn as_slice(&self) -> &[u8] {
+        &self.buffer[..self.len]
+    }
+
+    /// Returns the `(offset, len)` of each command within [`Transaction::as_slice`].
+    pub fn boundaries(&self) -> &[(usize, usize)] {
+        &self.boundaries[..self.count]
+    }
+
+    /// Returns the bytes of each command in order.
+    pub fn frames(&self) -> impl Iterator<Item = &[u8]> {
+        self.boundaries()
+            .iter()
+            .map(|&(offset, len)| &self.buffer[offset..offset + len])
+    }
+}
+
+/// Represents errors that can occur while adding a command to a [`Transaction`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum TransactionError {
+    /// The command could not be serialized, e.g. because it does not fit in the
+    /// remaining buffer.
+    Serialization(SerializationError),
+    /// The transaction already holds as many commands as it has boundary slots, however
+    /// much room is left in the buffer.
+    TooManyCommands {
+        /// The number of commands the transaction can hold, its `N`.
+        max: usize,
+    },
+}
+
+impl From<SerializationError> for TransactionError {
+    fn from(error: SerializationError) -> Self {
+        Self::Serialization(error)
+    }
+}
+
+impl fmt::Display for TransactionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::Serialization(error) => error.fmt(f),
+            Self::TooManyCommands { max } => {
+                write!(f, "transaction already holds its maximum of {max} commands")
+            }
+        }
+    }
+}
+
+impl core::error::Error for TransactionError {}
@@ -0,0 +1,53 @@
+//! Newtypes that tell apart fields which share a primitive type but not a meaning.
+//!
+//! Command variants keep plain integer fields, so these are only used by the typed
+//! constructors on each device's `Commands`, such as
+//! [`telexo::Commands::set_cv_quantized_note`](crate::devices::telexo::Commands::set_cv_quantized_note).
+
+/// A note index within the scale a device quantizes to, e.g. 0-127.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct QuantizedNote(pub u8);
+
+/// A raw CV value using the Teletype scaling, where 16384 corresponds to 10V.
+///
+/// The [`conv`](crate::conv) module converts voltages to this scaling.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RawCv(pub i16);
+
+/// A duration in milliseconds, as carried by the `ms` fields of slew, pulse and
+/// envelope commands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Millis(pub u16);
+
+impl Millis {
+    /// Converts a duration in seconds, rounding to the nearest millisecond.
+    ///
+    /// Saturates at `u16::MAX` (about 65.5 seconds); negative and NaN inputs become 0.
+    pub fn from_secs_f32(secs: f32) -> Self {
+        // Float-to-int `as` casts saturate and map NaN to 0.
+        Self((secs * 1000.0 + 0.5) as u16)
+    }
+
+    /// Converts a tempo to the duration of one beat, rounding to the nearest millisecond.
+    ///
+    /// A tempo of 0 saturates to `u16::MAX`.
+    pub const fn from_bpm(bpm: Bpm) -> Self {
+        if bpm.0 == 0 {
+            return Self(u16::MAX);
+        }
+        let bpm = bpm.0 as u32;
+        Self(((60_000 + bpm / 2) / bpm) as u16)
+    }
+}
+
+/// A tempo in beats per minute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Bpm(pub u16);